@@ -0,0 +1,37 @@
+/*****************************************
+ * Noms des arcanes majeurs (1 à 22)     *
+ * 22 = Le Mat                           *
+ * Autres langues: nom_arcane_langue     *
+ *****************************************/
+pub const NOMS_ARCANES: [&str; 22] = [
+    "Le Bateleur",
+    "La Papesse",
+    "L'Impératrice",
+    "L'Empereur",
+    "Le Pape",
+    "L'Amoureux",
+    "Le Chariot",
+    "La Justice",
+    "L'Hermite",
+    "La Roue de Fortune",
+    "La Force",
+    "Le Pendu",
+    "L'Arcane sans nom",
+    "Tempérance",
+    "Le Diable",
+    "La Maison Dieu",
+    "L'Étoile",
+    "La Lune",
+    "Le Soleil",
+    "Le Jugement",
+    "Le Monde",
+    "Le Mat",
+];
+
+pub fn nom_arcane(n: usize) -> Option<&'static str> {
+    if (1..=22).contains(&n) {
+        Some(NOMS_ARCANES[n - 1])
+    } else {
+        None
+    }
+}
@@ -0,0 +1,526 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::plan::Plan;
+use crate::reduction_theosophique;
+use crate::tableau::{Arcane, Cycle, Position, Tableau};
+
+// Variantes dans l'ordre des calques
+const VARIANTES: [&str; 4] = ["PA", "PB", "SA", "SB"];
+
+/*****************************************************
+ * Règle de visibilité d'une variante                *
+ * Références: "POSITION.VARIANTE", ex: "INT.PB"     *
+ * TOML: visible = "jamais",                         *
+ * visible = { different = ["CAI.PA", "CAI.SA"] },   *
+ * visible = { un_de = [{ visible = "INT.SA" }] }    *
+ *****************************************************/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Regle {
+    #[default]
+    Toujours,
+    Jamais,
+    // La variante référencée est visible
+    Visible(String),
+    // Les deux opérandes ont des valeurs différentes
+    Different(String, String),
+    NonNul(String),
+    Tous(Vec<Regle>),
+    UnDe(Vec<Regle>),
+}
+
+/*****************************************************
+ * Variante: somme des opérandes réduite au plafond  *
+ * (22 ou 9). Opérande: entrée (JOUR, MOIS, MOIS_B,  *
+ * ANNEE, AGE, PULSION), "POSITION.VARIANTE" ou      *
+ * nombre                                            *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Variante {
+    pub somme: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default = "plafond_22"))]
+    pub plafond: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub visible: Regle,
+}
+
+#[cfg(feature = "serde")]
+fn plafond_22() -> usize {
+    22
+}
+
+#[cfg(feature = "serde")]
+fn vrai() -> bool {
+    true
+}
+
+/*****************************************************
+ * Définition d'une position du tableau              *
+ * calque: préfixe des calques Photoshop, absent     *
+ * pour une position non dessinée                    *
+ * cadre: cadre dessiné derrière chaque valeur      *
+ * ({calque}{variante}-R), vrai par défaut           *
+ * pb, sb absents: valeur de PA, SA jamais visible   *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefinitionPosition {
+    pub nom: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub calque: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default = "vrai"))]
+    pub cadre: bool,
+    pub pa: Variante,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pb: Option<Variante>,
+    pub sa: Variante,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sb: Option<Variante>,
+}
+
+impl DefinitionPosition {
+    // Variantes qui peuvent être dessinées, ex: ["PA", "SA"] pour NEM
+    pub fn variantes_visibles(&self) -> Vec<&'static str> {
+        let b = |v: &Option<Variante>| v.as_ref().is_some_and(|v| v.visible != Regle::Jamais);
+        VARIANTES
+            .into_iter()
+            .zip([self.pa.visible != Regle::Jamais, b(&self.pb), self.sa.visible != Regle::Jamais, b(&self.sb)])
+            .filter(|(_, v)| *v)
+            .map(|(n, _)| n)
+            .collect()
+    }
+
+    // Section de la position: PPR pour PPR et son cycle PULSION_PPR, ACTION_PPR, REACTION_PPR
+    pub fn section(&self) -> &str {
+        self.nom.rsplit_once('_').map(|(_, base)| base).unwrap_or(&self.nom)
+    }
+}
+
+/*****************************************************
+ * Table des positions, évaluée dans l'ordre des     *
+ * références: une position peut en citer une autre  *
+ * placée plus bas. L'ordre de la table est celui    *
+ * des calques dessinés                              *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Definitions {
+    pub positions: Vec<DefinitionPosition>,
+}
+
+fn variante(somme: &[&str], plafond: usize, visible: Regle) -> Variante {
+    Variante { somme: somme.iter().map(|s| s.to_string()).collect(), plafond, visible }
+}
+
+fn visible(reference: &str) -> Regle {
+    Regle::Visible(reference.to_string())
+}
+
+fn different(a: &str, b: &str) -> Regle {
+    Regle::Different(a.to_string(), b.to_string())
+}
+
+fn non_nul(reference: &str) -> Regle {
+    Regle::NonNul(reference.to_string())
+}
+
+fn position(nom: &str, calque: Option<&str>, pa: Variante, pb: Option<Variante>, sa: Variante, sb: Option<Variante>) -> DefinitionPosition {
+    DefinitionPosition { nom: nom.to_string(), calque: calque.map(|c| c.to_string()), cadre: true, pa, pb, sa, sb }
+}
+
+// PA réduit à 22, SA = PA réduit à 9, visible s'il diffère
+fn position_pa_sa(nom: &str, entree: &str) -> DefinitionPosition {
+    let pa = format!("{}.PA", nom);
+    let sa = format!("{}.SA", nom);
+    position(nom, None, variante(&[entree], 22, Regle::Toujours), None, variante(&[&pa], 9, different(&pa, &sa)), None)
+}
+
+// Pulsion, action et réaction d'une position de base (PPR, NEM ou PEX)
+fn cycle(base: &str, prefixes: [&str; 3], pulsion_sa: Regle, avec_b: bool) -> [DefinitionPosition; 3] {
+    let pulsion = format!("PULSION_{}", base);
+    let action = format!("ACTION_{}", base);
+    let reaction = format!("REACTION_{}", base);
+    let b = |etape: &dyn Fn(&str) -> Variante, v: &str| avec_b.then(|| etape(v));
+    let etape_action = |v: &str| {
+        let base_v = format!("{}.{}", base, v);
+        variante(&["PULSION", &base_v], 22, visible(&base_v))
+    };
+    let etape_reaction = |v: &str| {
+        let base_v = format!("{}.{}", base, v);
+        variante(&[&format!("{}.{}", action, v), &base_v], 22, visible(&base_v))
+    };
+    [
+        position(&pulsion, Some(prefixes[0]), variante(&["PULSION"], 22, Regle::Toujours), None, variante(&["PULSION"], 22, pulsion_sa), None),
+        position(&action, Some(prefixes[1]), etape_action("PA"), b(&etape_action, "PB"), etape_action("SA"), b(&etape_action, "SB")),
+        position(&reaction, Some(prefixes[2]), etape_reaction("PA"), b(&etape_reaction, "PB"), etape_reaction("SA"), b(&etape_reaction, "SB")),
+    ]
+}
+
+impl Definitions {
+    /*************************************************
+     * Table intégrée: positions de base puis PPR,   *
+     * NEM et PEX, chacune suivie de son cycle       *
+     *************************************************/
+    pub fn par_defaut() -> Definitions {
+        let mut positions = vec![
+            position_pa_sa("CAI", "JOUR"),
+            position(
+                "INT",
+                None,
+                variante(&["MOIS"], 22, Regle::Toujours),
+                Some(variante(&["MOIS_B"], 22, Regle::Tous(vec![different("INT.PA", "INT.PB"), non_nul("INT.PB")]))),
+                variante(&["INT.PA"], 9, different("INT.PA", "INT.SA")),
+                Some(variante(&["INT.PB"], 9, Regle::Tous(vec![different("INT.SA", "INT.SB"), non_nul("INT.SB")]))),
+            ),
+            position_pa_sa("CAE", "ANNEE"),
+        ];
+        for (nom, base) in [("COI", "CAI"), ("COE", "CAE")] {
+            positions.push(position(
+                nom,
+                None,
+                variante(&[&format!("{}.PA", base), "INT.PA"], 22, Regle::Toujours),
+                Some(variante(&[&format!("{}.PA", base), "INT.PB"], 22, visible("INT.PB"))),
+                variante(&[&format!("{}.SA", base), "INT.SA"], 22, Regle::UnDe(vec![visible(&format!("{}.SA", base)), visible("INT.SA")])),
+                Some(variante(&[&format!("{}.SA", base), "INT.SB"], 22, Regle::UnDe(vec![visible(&format!("{}.SA", base)), visible("INT.SB")]))),
+            ));
+        }
+        let sa_cai_cae = || vec![visible("CAI.SA"), visible("CAE.SA")];
+        let ppr_sb = [visible("INT.SB")].into_iter().chain(sa_cai_cae()).collect();
+        positions.push(position(
+            "PPR",
+            Some("PPR"),
+            variante(&["CAI.PA", "INT.PA", "CAE.PA"], 22, Regle::Toujours),
+            Some(variante(&["CAI.PA", "INT.PB", "CAE.PA"], 22, Regle::Tous(vec![different("INT.PA", "INT.PB"), visible("INT.PB")]))),
+            variante(&["CAI.SA", "INT.SA", "CAE.SA"], 22, Regle::UnDe([visible("INT.SA")].into_iter().chain(sa_cai_cae()).collect())),
+            Some(variante(&["CAI.SA", "INT.SB", "CAE.SA"], 22, Regle::Tous(vec![different("INT.SA", "INT.SB"), Regle::UnDe(ppr_sb)]))),
+        ));
+        positions.extend(cycle("PPR", ["PPP", "APP", "RPP"], visible("PPR.SA"), true));
+        positions.push(position(
+            "NEM",
+            Some("NEM"),
+            variante(&["CAI.PA", "CAE.PA"], 22, Regle::Toujours),
+            None,
+            variante(&["CAI.SA", "CAE.SA"], 22, Regle::UnDe(sa_cai_cae())),
+            None,
+        ));
+        positions.extend(cycle("NEM", ["PNE", "ANE", "RNE"], Regle::Tous(vec![visible("NEM.SA"), visible("PPR.SA")]), false));
+        let pex_b = |v: &str| {
+            Regle::Tous(vec![
+                different("INT.PA", "INT.PB"),
+                Regle::UnDe(vec![visible(&format!("COI.{}", v)), visible(&format!("COE.{}", v))]),
+            ])
+        };
+        positions.push(position(
+            "PEX",
+            Some("PEX"),
+            variante(&["COI.PA", "COE.PA"], 22, Regle::Toujours),
+            Some(variante(&["COI.PB", "COE.PB"], 22, pex_b("PB"))),
+            variante(&["COI.SA", "COE.SA"], 22, Regle::UnDe(vec![visible("COI.SA"), visible("COE.SA")])),
+            Some(variante(&["COI.SB", "COE.SB"], 22, pex_b("SB"))),
+        ));
+        positions.extend(cycle("PEX", ["PPE", "APE", "RPE"], visible("PPR.SA"), true));
+        Definitions { positions }
+    }
+
+    // Table intégrée partagée avec std, reconstruite à chaque appel sinon
+    pub fn integree() -> Cow<'static, Definitions> {
+        #[cfg(feature = "std")]
+        {
+            Cow::Borrowed(table_integree())
+        }
+        #[cfg(not(feature = "std"))]
+        Cow::Owned(Definitions::par_defaut())
+    }
+
+    // Références résolues une fois pour toutes les évaluations du plan
+    pub fn compiler(&self) -> Result<Plan<'_>, ErreurDefinition> {
+        Plan::compiler(self)
+    }
+
+    pub fn position(&self, nom: &str) -> Option<&DefinitionPosition> {
+        self.positions.iter().find(|p| p.nom == nom)
+    }
+
+    /*************************************************
+     * Toutes les positions pour une naissance       *
+     * j, m, a et un âge révolu (plan compilé pour   *
+     * cette seule évaluation: Plan::evaluer pour    *
+     * en enchaîner plusieurs)                       *
+     *************************************************/
+    pub fn evaluer(&self, j: usize, m: usize, a: usize, age: usize) -> Result<Evaluation<'_>, ErreurDefinition> {
+        Ok(self.compiler()?.evaluer(j, m, a, age))
+    }
+}
+
+#[cfg(feature = "std")]
+fn table_integree() -> &'static Definitions {
+    static TABLE: std::sync::OnceLock<Definitions> = std::sync::OnceLock::new();
+    TABLE.get_or_init(Definitions::par_defaut)
+}
+
+// Plan de la table intégrée: compilé une fois avec std, à chaque appel sinon
+pub fn avec_plan_integre<R>(f: impl FnOnce(&Plan) -> R) -> R {
+    #[cfg(feature = "std")]
+    {
+        static PLAN: std::sync::OnceLock<Plan<'static>> = std::sync::OnceLock::new();
+        f(PLAN.get_or_init(|| table_integree().compiler().expect("table des positions intégrée valide")))
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let definitions = Definitions::par_defaut();
+        f(&definitions.compiler().expect("table des positions intégrée valide"))
+    }
+}
+
+impl Default for Definitions {
+    fn default() -> Definitions {
+        Definitions::par_defaut()
+    }
+}
+
+// Mois de la variante B: mois suivant pour une naissance après le 22
+pub(crate) fn mois_b(j: usize, m: usize) -> usize {
+    let mois = reduction_theosophique(m, false);
+    if j > 22 {
+        if mois + 1 > 12 { 1 } else { mois + 1 }
+    } else {
+        mois
+    }
+}
+
+/*****************************************************
+ * Erreur d'évaluation d'une table des positions     *
+ * Display: français, traduit par le crate principal *
+ * Dans: erreur survenue dans la définition d'une    *
+ * position                                          *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurDefinition {
+    PositionInconnue(String),
+    Circulaire(String),
+    Reference(String),
+    VarianteInconnue(String),
+    EntreeInconnue(String),
+    VarianteNonCalculee(String),
+    VisibiliteNonCalculee(String),
+    PositionManquante(String),
+    Dans(String, Box<ErreurDefinition>),
+}
+
+impl fmt::Display for ErreurDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurDefinition::PositionInconnue(p) => write!(f, "Position inconnue: {}", p),
+            ErreurDefinition::Circulaire(p) => write!(f, "Définition circulaire: {}", p),
+            ErreurDefinition::Reference(r) => write!(f, "Référence invalide: {} (POSITION.VARIANTE)", r),
+            ErreurDefinition::VarianteInconnue(r) => write!(f, "Variante inconnue: {}", r),
+            ErreurDefinition::EntreeInconnue(e) => write!(f, "Entrée inconnue: {}", e),
+            ErreurDefinition::VarianteNonCalculee(r) => write!(f, "Variante pas encore calculée: {}", r),
+            ErreurDefinition::VisibiliteNonCalculee(r) => write!(f, "Visibilité pas encore calculée: {}", r),
+            ErreurDefinition::PositionManquante(p) => write!(f, "Position manquante dans la table: {}", p),
+            ErreurDefinition::Dans(p, e) => write!(f, "{}: {}", p, e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErreurDefinition {}
+
+/*****************************************************
+ * Moteur d'évaluation: entrées nommées, positions   *
+ * déjà connues (ex: PPR d'un tableau combiné) et    *
+ * positions calculées à la demande                  *
+ *****************************************************/
+pub struct Moteur<'a> {
+    definitions: &'a Definitions,
+    entrees: Vec<(&'a str, usize)>,
+    positions: Vec<(&'a str, Position)>,
+    en_cours: Vec<&'a str>,
+}
+
+impl<'a> Moteur<'a> {
+    pub fn new(definitions: &'a Definitions) -> Moteur<'a> {
+        Moteur { definitions, entrees: vec![], positions: vec![], en_cours: vec![] }
+    }
+
+    pub fn entree(mut self, nom: &'a str, valeur: usize) -> Moteur<'a> {
+        self.entrees.push((nom, valeur));
+        self
+    }
+
+    pub fn position(mut self, nom: &'a str, position: Position) -> Moteur<'a> {
+        self.positions.push((nom, position));
+        self
+    }
+
+    pub fn evaluer(&mut self, nom: &str) -> Result<Position, ErreurDefinition> {
+        if let Some((_, p)) = self.positions.iter().find(|(n, _)| *n == nom) {
+            return Ok(*p);
+        }
+        let d = self.definitions.position(nom).ok_or_else(|| ErreurDefinition::PositionInconnue(nom.to_string()))?;
+        if self.en_cours.contains(&d.nom.as_str()) {
+            return Err(ErreurDefinition::Circulaire(d.nom.clone()));
+        }
+        self.en_cours.push(&d.nom);
+        let resultat = self.evaluer_definition(d);
+        self.en_cours.pop();
+        let p = resultat.map_err(|e| ErreurDefinition::Dans(d.nom.clone(), Box::new(e)))?;
+        self.positions.push((&d.nom, p));
+        Ok(p)
+    }
+
+    // Valeurs dans l'ordre PA, PB, SA, SB, puis visibilités
+    fn evaluer_definition(&mut self, d: &'a DefinitionPosition) -> Result<Position, ErreurDefinition> {
+        let mut valeurs = [None; 4];
+        let variantes = [Some(&d.pa), d.pb.as_ref(), Some(&d.sa), d.sb.as_ref()];
+        for (i, v) in variantes.iter().enumerate() {
+            valeurs[i] = Some(match v {
+                Some(v) => {
+                    let mut somme = 0;
+                    for operande in v.somme.iter() {
+                        somme += self.valeur(d, &valeurs, operande)?;
+                    }
+                    reduction_theosophique(somme, v.plafond == 9)
+                },
+                // B absente: valeur de A
+                None => valeurs[i - 1].unwrap_or(0),
+            });
+        }
+        let mut visibles = [None; 4];
+        for (i, v) in variantes.iter().enumerate() {
+            visibles[i] = Some(match v {
+                Some(v) => self.regle(d, &valeurs, &visibles, &v.visible)?,
+                None => false,
+            });
+        }
+        let arcane = |i: usize| Arcane::new(valeurs[i].unwrap_or(0), visibles[i].unwrap_or(false));
+        Ok(Position { pa: arcane(0), pb: arcane(1), sa: arcane(2), sb: arcane(3) })
+    }
+
+    // "CAI.SA" -> (position, indice de la variante)
+    pub(crate) fn reference(operande: &str) -> Result<(&str, usize), ErreurDefinition> {
+        let (position, v) = operande.split_once('.').ok_or_else(|| ErreurDefinition::Reference(operande.to_string()))?;
+        let i = VARIANTES.iter().position(|x| *x == v).ok_or_else(|| ErreurDefinition::VarianteInconnue(operande.to_string()))?;
+        Ok((position, i))
+    }
+
+    fn valeur(&mut self, courante: &DefinitionPosition, valeurs: &[Option<usize>; 4], operande: &str) -> Result<usize, ErreurDefinition> {
+        if let Ok(n) = operande.parse::<usize>() {
+            return Ok(n);
+        }
+        if !operande.contains('.') {
+            return self.entrees
+                .iter()
+                .find(|(n, _)| *n == operande)
+                .map(|(_, v)| *v)
+                .ok_or_else(|| ErreurDefinition::EntreeInconnue(operande.to_string()));
+        }
+        let (position, i) = Moteur::reference(operande)?;
+        if position == courante.nom {
+            return valeurs[i].ok_or_else(|| ErreurDefinition::VarianteNonCalculee(operande.to_string()));
+        }
+        let p = self.evaluer(position)?;
+        Ok(p.variantes()[i].1.valeur)
+    }
+
+    fn regle(&mut self, courante: &DefinitionPosition, valeurs: &[Option<usize>; 4], visibles: &[Option<bool>; 4], regle: &Regle) -> Result<bool, ErreurDefinition> {
+        Ok(match regle {
+            Regle::Toujours => true,
+            Regle::Jamais => false,
+            Regle::Visible(r) => {
+                let (position, i) = Moteur::reference(r)?;
+                if position == courante.nom {
+                    visibles[i].ok_or_else(|| ErreurDefinition::VisibiliteNonCalculee(r.clone()))?
+                } else {
+                    self.evaluer(position)?.variantes()[i].1.visible
+                }
+            },
+            Regle::Different(a, b) => self.valeur(courante, valeurs, a)? != self.valeur(courante, valeurs, b)?,
+            Regle::NonNul(a) => self.valeur(courante, valeurs, a)? != 0,
+            Regle::Tous(regles) => {
+                for r in regles.iter() {
+                    if !self.regle(courante, valeurs, visibles, r)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            },
+            Regle::UnDe(regles) => {
+                for r in regles.iter() {
+                    if self.regle(courante, valeurs, visibles, r)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            },
+        })
+    }
+}
+
+/*****************************************************
+ * Positions évaluées, dans l'ordre de la table      *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation<'a> {
+    pub jour: usize,
+    pub mois: usize,
+    pub annee: usize,
+    pub age: usize,
+    pub positions: Vec<(&'a DefinitionPosition, Position)>,
+}
+
+impl Evaluation<'_> {
+    pub fn position(&self, nom: &str) -> Option<Position> {
+        self.positions.iter().find(|(d, _)| d.nom == nom).map(|(_, p)| *p)
+    }
+
+    // Tableau des 17 positions habituelles, erreur si la table en omet une
+    pub fn tableau(&self) -> Result<Tableau, ErreurDefinition> {
+        let p = |nom: &str| self.position(nom).ok_or_else(|| ErreurDefinition::PositionManquante(nom.to_string()));
+        Ok(Tableau {
+            jour: self.jour,
+            mois: self.mois,
+            annee: self.annee,
+            age: self.age,
+            cai: p("CAI")?,
+            int: p("INT")?,
+            cae: p("CAE")?,
+            coi: p("COI")?,
+            coe: p("COE")?,
+            nem: p("NEM")?,
+            pex: p("PEX")?,
+            ppr: p("PPR")?,
+            cycle_ppr: Cycle { pulsion: p("PULSION_PPR")?, action: p("ACTION_PPR")?, reaction: p("REACTION_PPR")? },
+            cycle_nem: Cycle { pulsion: p("PULSION_NEM")?, action: p("ACTION_NEM")?, reaction: p("REACTION_NEM")? },
+            cycle_pex: Cycle { pulsion: p("PULSION_PEX")?, action: p("ACTION_PEX")?, reaction: p("REACTION_PEX")? },
+        })
+    }
+}
+
+/*********************************
+ * Une ligne par position, avec  *
+ * le nom donné dans la table    *
+ *********************************/
+impl fmt::Display for Evaluation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<12} {:>4} {:>4} {:>4} {:>4}", "", "PA", "PB", "SA", "SB")?;
+        for (d, p) in self.positions.iter() {
+            write!(f, "{:<12}", d.nom)?;
+            for (_, arcane) in p.variantes() {
+                match arcane.visible() {
+                    Some(x) => write!(f, " {:>4}", x)?,
+                    None => write!(f, " {:>4}", "-")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
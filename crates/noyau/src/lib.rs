@@ -0,0 +1,41 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+/*****************************************************
+ * Noyau de calcul: tableau, cycles et noms des      *
+ * arcanes, sans dépendance (no_std + alloc)         *
+ * std: table des positions intégrée construite une *
+ * seule fois                                        *
+ * Rendu PSD, textes et exports dans le crate        *
+ * cycles_numerologie_du_tarot                       *
+ *****************************************************/
+pub mod arcanes;
+pub mod definitions;
+pub mod plan;
+pub mod tableau;
+pub mod visibilite;
+
+pub use arcanes::nom_arcane;
+pub use definitions::{avec_plan_integre, DefinitionPosition, Definitions, ErreurDefinition, Evaluation, Moteur, Regle, Variante};
+pub use plan::{Partielle, Plan};
+pub use tableau::{calcul, Arcane, Cycle, Position, Tableau};
+pub use visibilite::{VarianteVue, VisibilityPolicy};
+
+/**************************
+ * Réduction théosophique *
+ * sw_9 = false -> 22     *
+ * sw_9 = true -> 9       *
+ **************************/
+pub fn reduction_theosophique(mut n: usize, sw_9: bool) -> usize {
+    let t = if sw_9 { 9 } else { 22 };
+    while n > t {
+        let mut sum = 0;
+        while n > 0 {
+            sum += n % 10;
+            n /= 10;
+        }
+        n = sum;
+    }
+    n
+}
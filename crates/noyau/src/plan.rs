@@ -0,0 +1,363 @@
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::definitions::{mois_b, DefinitionPosition, Definitions, ErreurDefinition, Evaluation, Moteur, Regle};
+use crate::reduction_theosophique;
+use crate::tableau::{Arcane, Cycle, Position, Tableau, NOMS_POSITIONS};
+
+// Entrées d'une évaluation, dans l'ordre de Plan::entrees
+const ENTREES: [&str; 6] = ["JOUR", "MOIS", "MOIS_B", "ANNEE", "AGE", "PULSION"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operande {
+    Nombre(usize),
+    Entree(usize),
+    // Variante déjà calculée de la position en cours
+    Propre(usize),
+    // (indice de la position dans la table, indice de la variante)
+    Valeur(usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RegleIndexee {
+    Toujours,
+    Jamais,
+    VisiblePropre(usize),
+    Visible(usize, usize),
+    Different(Operande, Operande),
+    NonNul(Operande),
+    Tous(Vec<RegleIndexee>),
+    UnDe(Vec<RegleIndexee>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VarianteIndexee {
+    somme: Vec<Operande>,
+    sw_9: bool,
+    visible: RegleIndexee,
+}
+
+type Variantes = [Option<VarianteIndexee>; 4];
+
+/*****************************************************
+ * Plan d'évaluation d'une table des positions,      *
+ * compilé une fois: références "POSITION.VARIANTE"  *
+ * résolues en indices, positions rangées dans       *
+ * l'ordre des dépendances                           *
+ * Mêmes erreurs que Moteur, détectées à la          *
+ * compilation: l'évaluation ne peut plus échouer    *
+ *****************************************************/
+#[derive(Debug, Clone)]
+pub struct Plan<'a> {
+    definitions: &'a Definitions,
+    variantes: Vec<Variantes>,
+    ordre: Vec<usize>,
+    // Positions citées directement par chaque position
+    dependances: Vec<Vec<usize>>,
+    // Indice dans la table de chaque position de NOMS_POSITIONS, si aucune ne manque
+    tableau: Option<[usize; 17]>,
+}
+
+impl<'a> Plan<'a> {
+    pub fn compiler(definitions: &'a Definitions) -> Result<Plan<'a>, ErreurDefinition> {
+        let mut c = Compilateur { definitions, variantes: vec![None; definitions.positions.len()], ordre: vec![], en_cours: vec![] };
+        for d in definitions.positions.iter() {
+            c.position(&d.nom)?;
+        }
+        let indices = NOMS_POSITIONS.map(|nom| definitions.positions.iter().position(|p| p.nom == nom));
+        let tableau = indices.iter().all(|i| i.is_some()).then(|| indices.map(|i| i.unwrap_or(0)));
+        let variantes: Vec<Variantes> = c.variantes.into_iter().flatten().collect();
+        let dependances = variantes.iter().map(dependances).collect();
+        Ok(Plan { definitions, variantes, ordre: c.ordre, dependances, tableau })
+    }
+
+    pub fn definitions(&self) -> &'a Definitions {
+        self.definitions
+    }
+
+    // Toutes les positions, dans l'ordre de la table
+    pub fn evaluer(&self, j: usize, m: usize, a: usize, age: usize) -> Evaluation<'a> {
+        let positions = self.calculer(&Plan::entrees(j, m, a, age), &[]);
+        Evaluation { jour: j, mois: m, annee: a, age, positions: self.definitions.positions.iter().zip(positions).collect() }
+    }
+
+    // Tableau des 17 positions habituelles, sans passer par les noms
+    pub fn tableau(&self, j: usize, m: usize, a: usize, age: usize) -> Result<Tableau, ErreurDefinition> {
+        let indices = self.indices()?;
+        let p = self.calculer(&Plan::entrees(j, m, a, age), &[]);
+        let [cai, int, cae, coi, coe, nem, pex, ppr, ppp, app, rpp, pne, ane, rne, ppe, ape, rpe] = indices.map(|i| p[i]);
+        Ok(Tableau {
+            jour: j,
+            mois: m,
+            annee: a,
+            age,
+            cai,
+            int,
+            cae,
+            coi,
+            coe,
+            nem,
+            pex,
+            ppr,
+            cycle_ppr: Cycle { pulsion: ppp, action: app, reaction: rpp },
+            cycle_nem: Cycle { pulsion: pne, action: ane, reaction: rne },
+            cycle_pex: Cycle { pulsion: ppe, action: ape, reaction: rpe },
+        })
+    }
+
+    // Cycles PPR, NEM et PEX d'une pulsion sur des positions de base données
+    pub fn cycles(&self, pulsion: usize, ppr: &Position, nem: &Position, pex: &Position) -> Result<[Cycle; 3], ErreurDefinition> {
+        let indices = self.indices()?;
+        let connues = [(indices[7], *ppr), (indices[5], *nem), (indices[6], *pex)];
+        let p = self.calculer(&[0, 0, 0, 0, 0, pulsion], &connues);
+        Ok([8, 11, 14].map(|k| Cycle { pulsion: p[indices[k]], action: p[indices[k + 1]], reaction: p[indices[k + 2]] }))
+    }
+
+    // Nombre de positions à calculer pour obtenir la position i, elle comprise
+    pub fn cout(&self, i: usize) -> usize {
+        let mut vues = vec![false; self.variantes.len()];
+        let mut pile = vec![i];
+        while let Some(p) = pile.pop() {
+            if !vues[p] {
+                vues[p] = true;
+                pile.extend(self.dependances[p].iter().copied());
+            }
+        }
+        vues.iter().filter(|v| **v).count()
+    }
+
+    pub fn partielle<'p>(&'p self, j: usize, m: usize, a: usize, age: usize) -> Partielle<'p, 'a> {
+        let vide = Arcane::new(0, false);
+        Partielle {
+            plan: self,
+            entrees: Plan::entrees(j, m, a, age),
+            positions: vec![Position { pa: vide, pb: vide, sa: vide, sb: vide }; self.variantes.len()],
+            calculees: vec![false; self.variantes.len()],
+        }
+    }
+
+    fn indices(&self) -> Result<[usize; 17], ErreurDefinition> {
+        self.tableau.ok_or_else(|| {
+            let manquante = NOMS_POSITIONS.iter().find(|nom| self.definitions.position(nom).is_none()).copied().unwrap_or_default();
+            ErreurDefinition::PositionManquante(manquante.to_string())
+        })
+    }
+
+    fn entrees(j: usize, m: usize, a: usize, age: usize) -> [usize; 6] {
+        [j, m, mois_b(j, m), a, age, reduction_theosophique(age + 1, false)]
+    }
+
+    // Positions dans l'ordre de la table, calculées dans l'ordre des dépendances
+    fn calculer(&self, entrees: &[usize; 6], connues: &[(usize, Position)]) -> Vec<Position> {
+        let vide = Arcane::new(0, false);
+        let mut positions = vec![Position { pa: vide, pb: vide, sa: vide, sb: vide }; self.variantes.len()];
+        for &i in self.ordre.iter() {
+            positions[i] = match connues.iter().find(|(c, _)| *c == i) {
+                Some((_, p)) => *p,
+                None => self.position(&self.variantes[i], entrees, &positions),
+            };
+        }
+        positions
+    }
+
+    // Valeurs dans l'ordre PA, PB, SA, SB, puis visibilités
+    fn position(&self, variantes: &Variantes, entrees: &[usize; 6], positions: &[Position]) -> Position {
+        let mut valeurs = [0; 4];
+        for (i, v) in variantes.iter().enumerate() {
+            valeurs[i] = match v {
+                Some(v) => {
+                    let somme = v.somme.iter().map(|o| valeur(*o, entrees, &valeurs, positions)).sum();
+                    reduction_theosophique(somme, v.sw_9)
+                },
+                // B absente: valeur de A
+                None => valeurs[i.saturating_sub(1)],
+            };
+        }
+        let mut visibles = [false; 4];
+        for (i, v) in variantes.iter().enumerate() {
+            visibles[i] = v.as_ref().is_some_and(|v| regle(&v.visible, entrees, &valeurs, &visibles, positions));
+        }
+        let arcane = |i: usize| Arcane::new(valeurs[i], visibles[i]);
+        Position { pa: arcane(0), pb: arcane(1), sa: arcane(2), sb: arcane(3) }
+    }
+}
+
+/*****************************************************
+ * Évaluation partielle d'une date: une position est *
+ * calculée à la demande avec ses dépendances, une   *
+ * seule fois (recherche de dates)                   *
+ *****************************************************/
+pub struct Partielle<'p, 'a> {
+    plan: &'p Plan<'a>,
+    entrees: [usize; 6],
+    positions: Vec<Position>,
+    calculees: Vec<bool>,
+}
+
+impl Partielle<'_, '_> {
+    // i: indice de la position dans la table
+    pub fn position(&mut self, i: usize) -> Position {
+        if !self.calculees[i] {
+            let plan = self.plan;
+            for &d in plan.dependances[i].iter() {
+                self.position(d);
+            }
+            self.positions[i] = plan.position(&plan.variantes[i], &self.entrees, &self.positions);
+            self.calculees[i] = true;
+        }
+        self.positions[i]
+    }
+}
+
+// Positions citées par les sommes et les règles d'une position, sans doublon
+fn dependances(variantes: &Variantes) -> Vec<usize> {
+    fn regle(r: &RegleIndexee, d: &mut Vec<usize>) {
+        match r {
+            RegleIndexee::Visible(p, _) => d.push(*p),
+            RegleIndexee::Different(a, b) => d.extend([a, b].into_iter().filter_map(operande)),
+            RegleIndexee::NonNul(a) => d.extend(operande(a)),
+            RegleIndexee::Tous(regles) | RegleIndexee::UnDe(regles) => regles.iter().for_each(|r| regle(r, d)),
+            _ => {},
+        }
+    }
+    fn operande(o: &Operande) -> Option<usize> {
+        if let Operande::Valeur(p, _) = o { Some(*p) } else { None }
+    }
+    let mut d = vec![];
+    for v in variantes.iter().flatten() {
+        d.extend(v.somme.iter().filter_map(operande));
+        regle(&v.visible, &mut d);
+    }
+    d.sort_unstable();
+    d.dedup();
+    d
+}
+
+fn arcane(p: &Position, variante: usize) -> Arcane {
+    match variante {
+        0 => p.pa,
+        1 => p.pb,
+        2 => p.sa,
+        _ => p.sb,
+    }
+}
+
+fn valeur(o: Operande, entrees: &[usize; 6], valeurs: &[usize; 4], positions: &[Position]) -> usize {
+    match o {
+        Operande::Nombre(n) => n,
+        Operande::Entree(e) => entrees[e],
+        Operande::Propre(v) => valeurs[v],
+        Operande::Valeur(p, v) => arcane(&positions[p], v).valeur,
+    }
+}
+
+fn regle(r: &RegleIndexee, entrees: &[usize; 6], valeurs: &[usize; 4], visibles: &[bool; 4], positions: &[Position]) -> bool {
+    match r {
+        RegleIndexee::Toujours => true,
+        RegleIndexee::Jamais => false,
+        RegleIndexee::VisiblePropre(v) => visibles[*v],
+        RegleIndexee::Visible(p, v) => arcane(&positions[*p], *v).visible,
+        RegleIndexee::Different(a, b) => valeur(*a, entrees, valeurs, positions) != valeur(*b, entrees, valeurs, positions),
+        RegleIndexee::NonNul(a) => valeur(*a, entrees, valeurs, positions) != 0,
+        RegleIndexee::Tous(regles) => regles.iter().all(|r| regle(r, entrees, valeurs, visibles, positions)),
+        RegleIndexee::UnDe(regles) => regles.iter().any(|r| regle(r, entrees, valeurs, visibles, positions)),
+    }
+}
+
+/*****************************************************
+ * Compilation dans l'ordre d'évaluation du Moteur:  *
+ * une position citée est compilée avant celle qui   *
+ * la cite, erreurs imbriquées de la même façon      *
+ *****************************************************/
+struct Compilateur<'a> {
+    definitions: &'a Definitions,
+    variantes: Vec<Option<Variantes>>,
+    ordre: Vec<usize>,
+    en_cours: Vec<usize>,
+}
+
+impl<'a> Compilateur<'a> {
+    fn position(&mut self, nom: &str) -> Result<usize, ErreurDefinition> {
+        let definitions = self.definitions;
+        let i = definitions.positions.iter().position(|p| p.nom == nom).ok_or_else(|| ErreurDefinition::PositionInconnue(nom.to_string()))?;
+        if self.variantes[i].is_some() {
+            return Ok(i);
+        }
+        let d = &definitions.positions[i];
+        if self.en_cours.contains(&i) {
+            return Err(ErreurDefinition::Circulaire(d.nom.clone()));
+        }
+        self.en_cours.push(i);
+        let resultat = self.variantes(d);
+        self.en_cours.pop();
+        self.variantes[i] = Some(resultat.map_err(|e| ErreurDefinition::Dans(d.nom.clone(), Box::new(e)))?);
+        self.ordre.push(i);
+        Ok(i)
+    }
+
+    fn variantes(&mut self, d: &'a DefinitionPosition) -> Result<Variantes, ErreurDefinition> {
+        let variantes = [Some(&d.pa), d.pb.as_ref(), Some(&d.sa), d.sb.as_ref()];
+        let mut sommes: [Vec<Operande>; 4] = Default::default();
+        for (i, v) in variantes.iter().enumerate() {
+            if let Some(v) = v {
+                for o in v.somme.iter() {
+                    sommes[i].push(self.operande(d, i, o)?);
+                }
+            }
+        }
+        let mut regles = [RegleIndexee::Jamais, RegleIndexee::Jamais, RegleIndexee::Jamais, RegleIndexee::Jamais];
+        for (i, v) in variantes.iter().enumerate() {
+            if let Some(v) = v {
+                regles[i] = self.regle(d, i, &v.visible)?;
+            }
+        }
+        let mut sommes = sommes.into_iter();
+        let mut regles = regles.into_iter();
+        Ok(variantes.map(|v| {
+            let (somme, visible) = (sommes.next().unwrap_or_default(), regles.next().unwrap_or(RegleIndexee::Jamais));
+            v.map(|v| VarianteIndexee { somme, sw_9: v.plafond == 9, visible })
+        }))
+    }
+
+    // calculees: variantes de la position courante dont la valeur est connue
+    fn operande(&mut self, courante: &DefinitionPosition, calculees: usize, operande: &str) -> Result<Operande, ErreurDefinition> {
+        if let Ok(n) = operande.parse::<usize>() {
+            return Ok(Operande::Nombre(n));
+        }
+        if !operande.contains('.') {
+            return ENTREES
+                .iter()
+                .position(|e| *e == operande)
+                .map(Operande::Entree)
+                .ok_or_else(|| ErreurDefinition::EntreeInconnue(operande.to_string()));
+        }
+        let (position, v) = Moteur::reference(operande)?;
+        if position == courante.nom {
+            return if v < calculees { Ok(Operande::Propre(v)) } else { Err(ErreurDefinition::VarianteNonCalculee(operande.to_string())) };
+        }
+        Ok(Operande::Valeur(self.position(position)?, v))
+    }
+
+    // Toutes les valeurs de la position courante sont connues, ses visibilités jusqu'à la variante i exclue
+    fn regle(&mut self, courante: &DefinitionPosition, i: usize, regle: &Regle) -> Result<RegleIndexee, ErreurDefinition> {
+        Ok(match regle {
+            Regle::Toujours => RegleIndexee::Toujours,
+            Regle::Jamais => RegleIndexee::Jamais,
+            Regle::Visible(r) => {
+                let (position, v) = Moteur::reference(r)?;
+                if position != courante.nom {
+                    RegleIndexee::Visible(self.position(position)?, v)
+                } else if v < i {
+                    RegleIndexee::VisiblePropre(v)
+                } else {
+                    return Err(ErreurDefinition::VisibiliteNonCalculee(r.clone()));
+                }
+            },
+            Regle::Different(a, b) => RegleIndexee::Different(self.operande(courante, 4, a)?, self.operande(courante, 4, b)?),
+            Regle::NonNul(a) => RegleIndexee::NonNul(self.operande(courante, 4, a)?),
+            Regle::Tous(regles) => RegleIndexee::Tous(regles.iter().map(|r| self.regle(courante, i, r)).collect::<Result<_, _>>()?),
+            Regle::UnDe(regles) => RegleIndexee::UnDe(regles.iter().map(|r| self.regle(courante, i, r)).collect::<Result<_, _>>()?),
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use core::fmt;
+use crate::definitions::avec_plan_integre;
+
+/********************************************
+ * Valeur d'une variante (PA, PB, SA ou SB) *
+ * visible = dessinée sur le tableau        *
+ ********************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arcane {
+    pub valeur: usize,
+    pub visible: bool,
+}
+
+impl Arcane {
+    pub fn new(valeur: usize, visible: bool) -> Arcane {
+        Arcane { valeur, visible }
+    }
+
+    pub fn visible(&self) -> Option<usize> {
+        if self.visible { Some(self.valeur) } else { None }
+    }
+}
+
+/******************************************
+ * Une position du tableau avec ses       *
+ * 4 variantes, les variantes inexistantes *
+ * (ex: PB de CAI) ne sont jamais visibles *
+ ******************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub pa: Arcane,
+    pub pb: Arcane,
+    pub sa: Arcane,
+    pub sb: Arcane,
+}
+
+impl Position {
+    // Dans l'ordre des calques: PA, PB, SA, SB
+    pub fn variantes(&self) -> [(&'static str, Arcane); 4] {
+        [("PA", self.pa), ("PB", self.pb), ("SA", self.sa), ("SB", self.sb)]
+    }
+}
+
+/*****************************************
+ * Cycle de l'année en cours (âge + 1)    *
+ *****************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cycle {
+    pub pulsion: Position,
+    pub action: Position,
+    pub reaction: Position,
+}
+
+/**************************
+ * Tableau complet calculé *
+ **************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tableau {
+    pub jour: usize,
+    pub mois: usize,
+    pub annee: usize,
+    pub age: usize,
+    pub cai: Position,
+    pub int: Position,
+    pub cae: Position,
+    pub coi: Position,
+    pub coe: Position,
+    pub nem: Position,
+    pub pex: Position,
+    pub ppr: Position,
+    pub cycle_ppr: Cycle,
+    pub cycle_nem: Cycle,
+    pub cycle_pex: Cycle,
+}
+
+// Noms des positions, dans l'ordre de Tableau::positions
+pub const NOMS_POSITIONS: [&str; 17] = [
+    "CAI", "INT", "CAE", "COI", "COE", "NEM", "PEX", "PPR",
+    "PULSION_PPR", "ACTION_PPR", "REACTION_PPR",
+    "PULSION_NEM", "ACTION_NEM", "REACTION_NEM",
+    "PULSION_PEX", "ACTION_PEX", "REACTION_PEX",
+];
+
+impl Tableau {
+    // Toutes les positions, de base puis du cycle en cours
+    pub fn positions(&self) -> [(&'static str, Position); 17] {
+        let p = [
+            self.cai, self.int, self.cae, self.coi, self.coe, self.nem, self.pex, self.ppr,
+            self.cycle_ppr.pulsion, self.cycle_ppr.action, self.cycle_ppr.reaction,
+            self.cycle_nem.pulsion, self.cycle_nem.action, self.cycle_nem.reaction,
+            self.cycle_pex.pulsion, self.cycle_pex.action, self.cycle_pex.reaction,
+        ];
+        core::array::from_fn(|i| (NOMS_POSITIONS[i], p[i]))
+    }
+
+    // Mêmes positions, modifiables (ex: VisibilityPolicy)
+    pub fn positions_mut(&mut self) -> [(&'static str, &mut Position); 17] {
+        let p = [
+            &mut self.cai, &mut self.int, &mut self.cae, &mut self.coi, &mut self.coe, &mut self.nem, &mut self.pex, &mut self.ppr,
+            &mut self.cycle_ppr.pulsion, &mut self.cycle_ppr.action, &mut self.cycle_ppr.reaction,
+            &mut self.cycle_nem.pulsion, &mut self.cycle_nem.action, &mut self.cycle_nem.reaction,
+            &mut self.cycle_pex.pulsion, &mut self.cycle_pex.action, &mut self.cycle_pex.reaction,
+        ];
+        let mut noms = NOMS_POSITIONS.into_iter();
+        p.map(|position| (noms.next().unwrap_or_default(), position))
+    }
+
+    // Position d'après son nom dans NOMS_POSITIONS
+    pub fn position(&self, nom: &str) -> Option<Position> {
+        self.positions().into_iter().find(|(n, _)| *n == nom).map(|(_, p)| p)
+    }
+}
+
+/*************************************
+ * Calcul du tableau                 *
+ * j, m, a: date de naissance        *
+ * age: âge révolu le jour de lecture *
+ * Formules: Definitions::integree,  *
+ * compilées une fois (Plan)         *
+ *************************************/
+pub fn calcul(j: usize, m: usize, a: usize, age: usize) -> Tableau {
+    avec_plan_integre(|plan| plan.tableau(j, m, a, age)).expect("table des positions intégrée valide")
+}
+
+// Position sans variante B (CAI, CAE, NEM, ...)
+pub fn position_pa_sa(pa: usize, sa: usize, v_sa: bool) -> Position {
+    Position {
+        pa: Arcane::new(pa, true),
+        pb: Arcane::new(pa, false),
+        sa: Arcane::new(sa, v_sa),
+        sb: Arcane::new(sa, false),
+    }
+}
+
+/*********************************************
+ * Cycles PPR, NEM et PEX d'une pulsion       *
+ * (annuelle, mensuelle ou journalière)       *
+ * sur des positions de base données, avec    *
+ * les formules de la table intégrée          *
+ *********************************************/
+pub fn cycles(pulsion: usize, ppr: &Position, nem: &Position, pex: &Position) -> [Cycle; 3] {
+    avec_plan_integre(|plan| plan.cycles(pulsion, ppr, nem, pex)).expect("table des positions intégrée valide")
+}
+
+/*****************************************
+ * Affichage en tableau texte            *
+ * "-" = variante non visible            *
+ * entete: ligne de naissance, omise     *
+ * sans date (tableau combiné)           *
+ * etapes: pulsion, action, réaction     *
+ *****************************************/
+impl Tableau {
+    pub fn ecrire(&self, f: &mut fmt::Formatter<'_>, entete: &dyn fmt::Display, etapes: [&str; 3]) -> fmt::Result {
+        let ligne = |f: &mut fmt::Formatter<'_>, etape: &str, nom: &str, p: &Position| -> fmt::Result {
+            // "Pulsion PPR" sur 12 colonnes, sans allocation
+            if etape.is_empty() {
+                write!(f, "{:<12}", nom)?;
+            } else {
+                write!(f, "{} {:<w$}", etape, nom, w = 11usize.saturating_sub(etape.chars().count()))?;
+            }
+            for (_, arcane) in p.variantes() {
+                match arcane.visible() {
+                    Some(x) => write!(f, " {:>4}", x)?,
+                    None => write!(f, " {:>4}", "-")?,
+                }
+            }
+            writeln!(f)
+        };
+        if self.annee > 0 {
+            writeln!(f, "{}", entete)?;
+        }
+        writeln!(f, "{:<12} {:>4} {:>4} {:>4} {:>4}", "", "PA", "PB", "SA", "SB")?;
+        ligne(f, "", "CAI", &self.cai)?;
+        ligne(f, "", "INT", &self.int)?;
+        ligne(f, "", "CAE", &self.cae)?;
+        ligne(f, "", "COI", &self.coi)?;
+        ligne(f, "", "COE", &self.coe)?;
+        ligne(f, "", "NEM", &self.nem)?;
+        ligne(f, "", "PEX", &self.pex)?;
+        ligne(f, "", "PPR", &self.ppr)?;
+        for (nom, c) in [("PPR", &self.cycle_ppr), ("NEM", &self.cycle_nem), ("PEX", &self.cycle_pex)] {
+            ligne(f, etapes[0], nom, &c.pulsion)?;
+            ligne(f, etapes[1], nom, &c.action)?;
+            ligne(f, etapes[2], nom, &c.reaction)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Tableau {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.ecrire(
+            f,
+            &format_args!("Naissance: {:02}.{:02}.{:04}  Âge: {}", self.jour, self.mois, self.annee, self.age),
+            ["Pulsion", "Action", "Réaction"],
+        )
+    }
+}
@@ -0,0 +1,81 @@
+use crate::definitions::{DefinitionPosition, Definitions, Evaluation};
+use crate::tableau::{Arcane, Position, Tableau};
+
+/*****************************************************
+ * Politique de visibilité des variantes, appliquée  *
+ * aux positions calculées (tableau, évaluation)     *
+ * avant analyse, affichage et rendu                 *
+ * Regles: règles de la table (SA masqué s'il égale  *
+ * PA, PB sans décalage du mois, cascades COI, COE,  *
+ * PEX, PPR)                                         *
+ * Toutes: toute variante dessinable de la table,    *
+ * même identique                                    *
+ * Principales: PA et PB selon les règles, SA et SB  *
+ * masqués                                           *
+ * Predicat: choix par variante, d'après la          *
+ * visibilité donnée par les règles                  *
+ *****************************************************/
+#[derive(Debug, Clone, Copy, Default)]
+pub enum VisibilityPolicy {
+    #[default]
+    Regles,
+    Toutes,
+    Principales,
+    Predicat(fn(&VarianteVue) -> bool),
+}
+
+// Variante soumise à la politique
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VarianteVue<'a> {
+    pub position: &'a str,
+    pub variante: &'static str,
+    // Valeur et visibilité selon les règles de la table
+    pub arcane: Arcane,
+    // Variante définie et pas "jamais" visible (calques attendus)
+    pub dessinable: bool,
+}
+
+impl VisibilityPolicy {
+    pub fn visible(&self, vue: &VarianteVue) -> bool {
+        match self {
+            VisibilityPolicy::Regles => vue.arcane.visible,
+            VisibilityPolicy::Toutes => vue.dessinable,
+            VisibilityPolicy::Principales => vue.arcane.visible && vue.variante.starts_with('P'),
+            VisibilityPolicy::Predicat(predicat) => predicat(vue),
+        }
+    }
+
+    pub fn position(&self, definition: &DefinitionPosition, position: Position) -> Position {
+        let dessinables = definition.variantes_visibles();
+        let v = position.variantes().map(|(variante, arcane)| {
+            let vue = VarianteVue { position: &definition.nom, variante, arcane, dessinable: dessinables.contains(&variante) };
+            Arcane::new(arcane.valeur, self.visible(&vue))
+        });
+        Position { pa: v[0], pb: v[1], sa: v[2], sb: v[3] }
+    }
+
+    // Tableau des 17 positions, définitions de la table intégrée
+    pub fn tableau(&self, tableau: &Tableau) -> Tableau {
+        let mut resultat = *tableau;
+        if let VisibilityPolicy::Regles = self {
+            return resultat;
+        }
+        let definitions = Definitions::integree();
+        for (nom, position) in resultat.positions_mut() {
+            if let Some(d) = definitions.position(nom) {
+                *position = self.position(d, *position);
+            }
+        }
+        resultat
+    }
+
+    pub fn evaluation<'a>(&self, mut evaluation: Evaluation<'a>) -> Evaluation<'a> {
+        if let VisibilityPolicy::Regles = self {
+            return evaluation;
+        }
+        for (d, position) in evaluation.positions.iter_mut() {
+            *position = self.position(d, *position);
+        }
+        evaluation
+    }
+}
@@ -0,0 +1,115 @@
+use cycles_numerologie_du_tarot_noyau::{calcul, reduction_theosophique, Arcane, DefinitionPosition, Definitions, ErreurDefinition, Moteur, Position, Regle, Variante};
+
+fn variante(somme: &[&str], plafond: usize, visible: Regle) -> Variante {
+    Variante { somme: somme.iter().map(|s| s.to_string()).collect(), plafond, visible }
+}
+
+fn position(nom: &str, pa: Variante, sa: Variante) -> DefinitionPosition {
+    DefinitionPosition { nom: nom.to_string(), calque: None, cadre: true, pa, pb: None, sa, sb: None }
+}
+
+fn different(a: &str, b: &str) -> Regle {
+    Regle::Different(a.to_string(), b.to_string())
+}
+
+#[test]
+fn reductions() {
+    for (n, a_22, a_9) in [(0, 0, 0), (9, 9, 9), (10, 10, 1), (22, 22, 4), (23, 5, 5), (99, 18, 9), (1946, 20, 2)] {
+        assert_eq!(reduction_theosophique(n, false), a_22, "{}", n);
+        assert_eq!(reduction_theosophique(n, true), a_9, "{}", n);
+    }
+}
+
+#[test]
+fn table_personnalisee() {
+    // DEP: JOUR + MOIS; SOM cite DEP, placée plus bas dans la table
+    let definitions = Definitions {
+        positions: vec![
+            position("SOM", variante(&["DEP.PA", "DEP.SA", "5"], 22, Regle::Toujours), variante(&["SOM.PA"], 9, Regle::Jamais)),
+            position("DEP", variante(&["JOUR", "MOIS"], 22, Regle::Toujours), variante(&["DEP.PA"], 9, different("DEP.PA", "DEP.SA"))),
+        ],
+    };
+    let evaluation = definitions.evaluer(14, 6, 1946, 40).unwrap();
+    assert_eq!(evaluation.positions.iter().map(|(d, _)| d.nom.as_str()).collect::<Vec<_>>(), ["SOM", "DEP"]);
+    let dep = evaluation.position("DEP").unwrap();
+    assert_eq!((dep.pa, dep.sa), (Arcane::new(20, true), Arcane::new(2, true)));
+    // B absente: valeur de A, jamais visible
+    assert_eq!(dep.pb, Arcane::new(20, false));
+    let som = evaluation.position("SOM").unwrap();
+    assert_eq!((som.pa, som.sa), (Arcane::new(9, true), Arcane::new(9, false)));
+    // Les 17 positions habituelles manquent
+    assert_eq!(evaluation.tableau(), Err(ErreurDefinition::PositionManquante("CAI".to_string())));
+}
+
+#[test]
+fn regles_de_visibilite() {
+    let regle = |visible: Regle| {
+        let definitions = Definitions { positions: vec![position("X", variante(&["JOUR"], 22, Regle::Toujours), variante(&["X.PA"], 9, visible))] };
+        Moteur::new(&definitions).entree("JOUR", 14).evaluer("X").map(|p| p.sa.visible)
+    };
+    assert_eq!(regle(Regle::Visible("X.PA".to_string())), Ok(true));
+    assert_eq!(regle(Regle::NonNul("0".to_string())), Ok(false));
+    assert_eq!(regle(Regle::Tous(vec![Regle::Toujours, Regle::Jamais])), Ok(false));
+    assert_eq!(regle(Regle::UnDe(vec![Regle::Jamais, different("X.PA", "X.SA")])), Ok(true));
+    // Visibilité de SB pas encore évaluée pendant celle de SA
+    assert_eq!(
+        regle(Regle::Visible("X.SB".to_string())),
+        Err(ErreurDefinition::Dans("X".to_string(), Box::new(ErreurDefinition::VisibiliteNonCalculee("X.SB".to_string()))))
+    );
+}
+
+#[test]
+fn erreurs_de_table() {
+    let definitions = Definitions {
+        positions: vec![
+            position("A", variante(&["B.PA"], 22, Regle::Toujours), variante(&["A.PA"], 9, Regle::Toujours)),
+            position("B", variante(&["A.PA"], 22, Regle::Toujours), variante(&["B.PA"], 9, Regle::Toujours)),
+            position("C", variante(&["INCONNUE"], 22, Regle::Toujours), variante(&["C.PA"], 9, Regle::Toujours)),
+            position("D", variante(&["A.XX"], 22, Regle::Toujours), variante(&["D.PA"], 9, Regle::Toujours)),
+        ],
+    };
+    let dans = |p: &str, e: ErreurDefinition| ErreurDefinition::Dans(p.to_string(), Box::new(e));
+    let mut moteur = Moteur::new(&definitions);
+    assert_eq!(moteur.evaluer("A"), Err(dans("A", dans("B", ErreurDefinition::Circulaire("A".to_string())))));
+    assert_eq!(moteur.evaluer("C"), Err(dans("C", ErreurDefinition::EntreeInconnue("INCONNUE".to_string()))));
+    assert_eq!(moteur.evaluer("D"), Err(dans("D", ErreurDefinition::VarianteInconnue("A.XX".to_string()))));
+    assert_eq!(moteur.evaluer("Z"), Err(ErreurDefinition::PositionInconnue("Z".to_string())));
+    assert_eq!(dans("A", ErreurDefinition::Circulaire("A".to_string())).to_string(), "A: Définition circulaire: A");
+
+    // Position connue d'avance: la circularité est rompue
+    let connue = Position { pa: Arcane::new(3, true), pb: Arcane::new(3, false), sa: Arcane::new(3, false), sb: Arcane::new(3, false) };
+    let mut moteur = Moteur::new(&definitions).position("B", connue);
+    assert_eq!(moteur.evaluer("A").map(|p| p.pa.valeur), Ok(3));
+}
+
+#[test]
+fn table_integree() {
+    let definitions = Definitions::integree();
+    for (j, m, a, age) in [(14, 6, 1946, 40), (29, 12, 1999, 0), (23, 2, 1987, 40), (1, 1, 2000, 10)] {
+        let evaluation = definitions.evaluer(j, m, a, age).unwrap();
+        assert_eq!(evaluation.positions.len(), 17);
+        assert_eq!(evaluation.tableau(), Ok(calcul(j, m, a, age)));
+    }
+    // Mois B: mois suivant après le 22, décembre suivi de janvier
+    let mois_b = |j: usize, m: usize| {
+        let definitions = Definitions { positions: vec![position("M", variante(&["MOIS_B"], 22, Regle::Toujours), variante(&["M.PA"], 9, Regle::Jamais))] };
+        definitions.evaluer(j, m, 2000, 0).unwrap().position("M").unwrap().pa.valeur
+    };
+    assert_eq!([mois_b(22, 12), mois_b(23, 12), mois_b(23, 6)], [12, 1, 7]);
+}
+
+#[test]
+fn balayage_rapide() {
+    // Table compilée une fois: 1900–2100 jour par jour, même en debug
+    let debut = std::time::Instant::now();
+    let mut total = 0;
+    for annee in 1900..=2100 {
+        for mois in 1..=12 {
+            for jour in 1..=28 {
+                total += calcul(jour, mois, annee, 40).ppr.pa.valeur;
+            }
+        }
+    }
+    assert!(total > 0);
+    assert!(debut.elapsed().as_secs() < 10, "{:?}", debut.elapsed());
+}
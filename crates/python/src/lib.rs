@@ -0,0 +1,7 @@
+/*****************************************************
+ * Bibliothèque partagée du module Python            *
+ * (feature "python" de la crate principale, qui     *
+ * définit PyInit_cycles_numerologie_du_tarot)       *
+ * maturin build --release (voir pyproject.toml)     *
+ *****************************************************/
+pub use cycles_numerologie_du_tarot;
@@ -0,0 +1,7 @@
+/*****************************************************
+ * Bibliothèque wasm des liaisons JavaScript         *
+ * (feature "wasm" de la crate principale):          *
+ * wasm-pack build --release --target bundler        *
+ *     crates/wasm                                   *
+ *****************************************************/
+pub use cycles_numerologie_du_tarot::wasm::*;
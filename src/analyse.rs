@@ -0,0 +1,206 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::langue::{message, nom_arcane_langue, Erreur, Langue, Localise};
+use crate::Tableau;
+
+/*****************************************************
+ * Regroupement d'arcanes, ex: parité               *
+ * Un arcane peut n'appartenir à aucun groupe       *
+ * Format texte: nom:groupe=1,3,5;autre=2,4,6       *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Groupement {
+    pub nom: String,
+    pub groupes: Vec<(String, Vec<usize>)>,
+}
+
+impl Groupement {
+    pub fn new(nom: &str, groupes: Vec<(String, Vec<usize>)>) -> Groupement {
+        Groupement { nom: nom.to_string(), groupes }
+    }
+
+    pub fn parite() -> Groupement {
+        Groupement::parite_langue(Langue::default())
+    }
+
+    pub fn parite_langue(langue: Langue) -> Groupement {
+        Groupement::new(&message(langue, "groupement-parite", &[]), vec![
+            (message(langue, "groupe-impair", &[]), (1..=22).filter(|x| x % 2 == 1).collect()),
+            (message(langue, "groupe-pair", &[]), (1..=22).filter(|x| x % 2 == 0).collect()),
+        ])
+    }
+
+    // Figures masculines (actif) et féminines (réceptif), les autres sont neutres
+    pub fn actif_receptif() -> Groupement {
+        Groupement::actif_receptif_langue(Langue::default())
+    }
+
+    pub fn actif_receptif_langue(langue: Langue) -> Groupement {
+        Groupement::new(&message(langue, "groupement-actif-receptif", &[]), vec![
+            (message(langue, "groupe-actif", &[]), vec![1, 4, 5, 6, 7, 9, 12, 15, 19, 22]),
+            (message(langue, "groupe-receptif", &[]), vec![2, 3, 8, 11, 14, 17, 18, 21]),
+        ])
+    }
+
+    pub fn par_defaut() -> Vec<Groupement> {
+        Groupement::par_defaut_langue(Langue::default())
+    }
+
+    pub fn par_defaut_langue(langue: Langue) -> Vec<Groupement> {
+        vec![Groupement::parite_langue(langue), Groupement::actif_receptif_langue(langue)]
+    }
+
+    pub fn groupe(&self, arcane: usize) -> Option<&str> {
+        self.groupes.iter().find(|(_, a)| a.contains(&arcane)).map(|(g, _)| g.as_str())
+    }
+}
+
+impl FromStr for Groupement {
+    type Err = Erreur;
+
+    fn from_str(s: &str) -> Result<Groupement, Erreur> {
+        let (nom, groupes) = s
+            .split_once(':')
+            .ok_or_else(|| Erreur::new("erreur-groupement", &[("texte", s.to_string())]))?;
+        let mut resultat = vec![];
+        for groupe in groupes.split(';').filter(|g| !g.trim().is_empty()) {
+            let (g, arcanes) = groupe
+                .split_once('=')
+                .ok_or_else(|| Erreur::new("erreur-groupe", &[("texte", groupe.trim().to_string())]))?;
+            let arcanes = arcanes
+                .split(',')
+                .map(|a| match a.trim().parse::<usize>() {
+                    Ok(x) if (1..=22).contains(&x) => Ok(x),
+                    _ => Err(Erreur::new("erreur-arcane", &[("texte", a.trim().to_string())])),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            resultat.push((g.trim().to_string(), arcanes));
+        }
+        if resultat.is_empty() {
+            return Err(Erreur::new("erreur-groupement-vide", &[("texte", s.to_string())]));
+        }
+        Ok(Groupement::new(nom.trim(), resultat))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Repetition {
+    pub arcane: usize,
+    pub nom: String,
+    // POSITION.VARIANTE, ex: "NEM.SA"
+    pub positions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompteGroupe {
+    pub groupe: String,
+    pub nombre: usize,
+    pub part: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dominance {
+    pub groupement: String,
+    pub groupes: Vec<CompteGroupe>,
+    // None en cas d'égalité
+    pub dominant: Option<String>,
+}
+
+/*****************************************************
+ * Analyse des arcanes visibles d'un tableau         *
+ * (positions de base et cycle en cours):            *
+ * arcanes répétés d'une position à l'autre,         *
+ * arcanes absents et dominantes                     *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Analyse {
+    // Nombre d'arcanes visibles (une fois par position)
+    pub total: usize,
+    // Arcanes présents au moins deux fois, du plus répété au moins répété
+    pub repetitions: Vec<Repetition>,
+    pub absents: Vec<usize>,
+    pub dominances: Vec<Dominance>,
+}
+
+pub fn analyser(tableau: &Tableau, groupements: &[Groupement]) -> Analyse {
+    analyser_langue(tableau, groupements, Langue::default())
+}
+
+// Noms des arcanes répétés dans la langue demandée
+pub fn analyser_langue(tableau: &Tableau, groupements: &[Groupement], langue: Langue) -> Analyse {
+    let mut occurrences: Vec<Vec<String>> = vec![vec![]; 22];
+    for (position, p) in tableau.positions().iter() {
+        let mut vus = vec![];
+        for (variante, arcane) in p.variantes().iter() {
+            // Un arcane compte une fois par position, même s'il y figure en A et en S
+            // (valeur hors 1..=22, ex: année 0: ignorée)
+            if let Some(x) = arcane.visible().filter(|x| (1..=22).contains(x) && !vus.contains(x)) {
+                vus.push(x);
+                occurrences[x - 1].push(format!("{}.{}", position, variante));
+            }
+        }
+    }
+    let total = occurrences.iter().map(|o| o.len()).sum();
+    let mut repetitions: Vec<Repetition> = occurrences
+        .iter()
+        .enumerate()
+        .filter(|(_, o)| o.len() > 1)
+        .map(|(i, o)| Repetition { arcane: i + 1, nom: nom_arcane_langue(i + 1, langue).unwrap_or_default(), positions: o.clone() })
+        .collect();
+    repetitions.sort_by(|a, b| b.positions.len().cmp(&a.positions.len()).then(a.arcane.cmp(&b.arcane)));
+    let absents = (1..=22).filter(|x| occurrences[x - 1].is_empty()).collect();
+    let dominances = groupements
+        .iter()
+        .map(|g| {
+            let groupes: Vec<CompteGroupe> = g.groupes
+                .iter()
+                .map(|(nom, arcanes)| {
+                    let nombre = arcanes.iter().filter_map(|x| x.checked_sub(1).and_then(|i| occurrences.get(i))).map(|o| o.len()).sum();
+                    let part = if total > 0 { nombre as f64 / total as f64 } else { 0.0 };
+                    CompteGroupe { groupe: nom.clone(), nombre, part }
+                })
+                .collect();
+            let max = groupes.iter().map(|c| c.nombre).max().unwrap_or(0);
+            let dominant = match groupes.iter().filter(|c| c.nombre == max).collect::<Vec<_>>()[..] {
+                [c] if max > 0 => Some(c.groupe.clone()),
+                _ => None,
+            };
+            Dominance { groupement: g.nom.clone(), groupes, dominant }
+        })
+        .collect();
+    Analyse { total, repetitions, absents, dominances }
+}
+
+impl Localise for Analyse {
+    fn fmt_langue(&self, f: &mut fmt::Formatter<'_>, langue: Langue) -> fmt::Result {
+        writeln!(f, "{}", message(langue, "analyse-repetitions", &[]))?;
+        if self.repetitions.is_empty() {
+            writeln!(f, "  {}", message(langue, "analyse-aucune", &[]))?;
+        }
+        for r in self.repetitions.iter() {
+            writeln!(f, "  {:>2} {:<20} x{}  {}", r.arcane, r.nom, r.positions.len(), r.positions.join(", "))?;
+        }
+        let absents: Vec<String> = self.absents.iter().map(|x| x.to_string()).collect();
+        let liste = if absents.is_empty() { message(langue, "analyse-aucun", &[]) } else { absents.join(", ") };
+        writeln!(f, "{}", message(langue, "analyse-absents", &[("liste", liste)]))?;
+        for d in self.dominances.iter() {
+            let groupes: Vec<String> = d.groupes
+                .iter()
+                .map(|c| format!("{} {} ({:.0}%)", c.groupe, c.nombre, c.part * 100.0))
+                .collect();
+            writeln!(f, "{}: {} -> {}", d.groupement, groupes.join(", "), d.dominant.clone().unwrap_or_else(|| message(langue, "analyse-equilibre", &[])))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Analyse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_langue(f, Langue::default())
+    }
+}
@@ -0,0 +1,136 @@
+use std::error::Error;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::codecs::webp::WebPEncoder;
+use image::{Delay, ExtendedColorType, Frame, ImageEncoder, RgbaImage};
+use crate::template::ErreurTemplate;
+
+/*****************************************************
+ * Animation du tableau au fil des âges: une image   *
+ * par âge, même durée pour chacune, lue en boucle   *
+ * Gif: 256 couleurs par image                       *
+ * Apng: PNG animé, sans perte                       *
+ * Webp: WebP animé, sans perte                      *
+ *****************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FormatAnimation {
+    Gif,
+    Apng,
+    Webp,
+}
+
+// Images de même taille, durée de chaque image en millisecondes
+pub fn encoder_animation(images: &[RgbaImage], duree_ms: u32, format: FormatAnimation) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (w, h) = dimensions(images)?;
+    if images.iter().any(|i| i.dimensions() != (w, h)) {
+        return Err(ErreurTemplate::TaillesAnimation.into());
+    }
+    match format {
+        FormatAnimation::Gif => encoder_gif_anime(images, duree_ms),
+        FormatAnimation::Apng => encoder_apng(images, duree_ms),
+        FormatAnimation::Webp => encoder_webp_anime(images, duree_ms),
+    }
+}
+
+// Taille de la première image, ni absente ni vide
+fn dimensions(images: &[RgbaImage]) -> Result<(u32, u32), ErreurTemplate> {
+    match images.first().map(|i| i.dimensions()) {
+        Some((w, h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(ErreurTemplate::AnimationVide),
+    }
+}
+
+pub fn encoder_gif_anime(images: &[RgbaImage], duree_ms: u32) -> Result<Vec<u8>, Box<dyn Error>> {
+    let erreur = |e: image::ImageError| ErreurTemplate::Encodage { format: "GIF", erreur: e.to_string() };
+    let mut gif = Vec::new();
+    {
+        let mut encodeur = GifEncoder::new(&mut gif);
+        encodeur.set_repeat(Repeat::Infinite).map_err(erreur)?;
+        let delai = Delay::from_numer_denom_ms(duree_ms, 1);
+        encodeur
+            .encode_frames(images.iter().map(|i| Frame::from_parts(i.clone(), 0, 0, delai)))
+            .map_err(erreur)?;
+    }
+    Ok(gif)
+}
+
+// Délai en millièmes de seconde, sur 16 bits
+pub fn encoder_apng(images: &[RgbaImage], duree_ms: u32) -> Result<Vec<u8>, Box<dyn Error>> {
+    let erreur = |e: png::EncodingError| ErreurTemplate::Encodage { format: "APNG", erreur: e.to_string() };
+    let (w, h) = images.first().map(|i| i.dimensions()).unwrap_or((0, 0));
+    let mut apng = Vec::new();
+    let mut encodeur = png::Encoder::new(&mut apng, w, h);
+    encodeur.set_color(png::ColorType::Rgba);
+    encodeur.set_depth(png::BitDepth::Eight);
+    encodeur.set_animated(images.len() as u32, 0).map_err(erreur)?;
+    encodeur.set_frame_delay(duree_ms.min(u16::MAX as u32) as u16, 1000).map_err(erreur)?;
+    let mut ecrivain = encodeur.write_header().map_err(erreur)?;
+    for image in images.iter() {
+        ecrivain.write_image_data(image.as_raw()).map_err(erreur)?;
+    }
+    ecrivain.finish().map_err(erreur)?;
+    Ok(apng)
+}
+
+/*****************************************************
+ * WebP animé: chaque image encodée seule sans perte *
+ * (VP8L), puis assemblée dans les morceaux VP8X,    *
+ * ANIM et un ANMF par image                         *
+ *****************************************************/
+pub fn encoder_webp_anime(images: &[RgbaImage], duree_ms: u32) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (w, h) = dimensions(images)?;
+    let mut corps = b"WEBP".to_vec();
+    // Animation et transparence, taille du canevas - 1
+    let mut vp8x = vec![0x12, 0, 0, 0];
+    vp8x.extend(u24(w - 1));
+    vp8x.extend(u24(h - 1));
+    morceau(&mut corps, b"VP8X", &vp8x);
+    // Fond transparent, boucle infinie
+    morceau(&mut corps, b"ANIM", &[0, 0, 0, 0, 0, 0]);
+    for image in images.iter() {
+        let mut anmf = [u24(0), u24(0), u24(w - 1), u24(h - 1), u24(duree_ms.min(0xff_ffff))].concat();
+        // Sans mélange avec l'image précédente, pas d'effacement
+        anmf.push(0x02);
+        anmf.extend(morceau_vp8l(image)?);
+        morceau(&mut corps, b"ANMF", &anmf);
+    }
+    let mut webp = b"RIFF".to_vec();
+    webp.extend((corps.len() as u32).to_le_bytes());
+    webp.extend(corps);
+    Ok(webp)
+}
+
+// Morceau VP8L (en-tête compris) d'une image WebP sans perte
+fn morceau_vp8l(image: &RgbaImage) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut webp = Vec::new();
+    WebPEncoder::new_lossless(&mut webp)
+        .write_image(image.as_raw(), image.width(), image.height(), ExtendedColorType::Rgba8)
+        .map_err(|e| ErreurTemplate::Encodage { format: "WebP", erreur: e.to_string() })?;
+    // RIFF, taille, WEBP puis les morceaux
+    let mut i = 12;
+    while i + 8 <= webp.len() {
+        let taille = u32::from_le_bytes([webp[i + 4], webp[i + 5], webp[i + 6], webp[i + 7]]) as usize;
+        let fin = (i + 8 + taille + taille % 2).min(webp.len());
+        if &webp[i..i + 4] == b"VP8L" {
+            return Ok(webp[i..fin].to_vec());
+        }
+        i = fin;
+    }
+    Err(ErreurTemplate::WebpVp8l.into())
+}
+
+// Nom, taille, données, complété à une longueur paire
+fn morceau(webp: &mut Vec<u8>, nom: &[u8; 4], donnees: &[u8]) {
+    webp.extend(nom);
+    webp.extend((donnees.len() as u32).to_le_bytes());
+    webp.extend(donnees);
+    if donnees.len() % 2 == 1 {
+        webp.push(0);
+    }
+}
+
+fn u24(n: u32) -> [u8; 3] {
+    let [a, b, c, _] = n.to_le_bytes();
+    [a, b, c]
+}
@@ -0,0 +1,1104 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::ops::RangeInclusive;
+use std::process::ExitCode;
+use clap::error::{ContextKind, ContextValue, ErrorKind as ErreurClap};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use cycles_numerologie_du_tarot::animation::{encoder_animation, FormatAnimation};
+use cycles_numerologie_du_tarot::lot::{lire_clients, rendre_lot, OptionsLot};
+use cycles_numerologie_du_tarot::compatibilite::rendre_compatibilite;
+use cycles_numerologie_du_tarot::calque::ouvrir_nommage;
+use cycles_numerologie_du_tarot::definitions::ouvrir_definitions;
+use cycles_numerologie_du_tarot::date::{plage_ages, verifier_age, ErreurDate, AGE_MAX};
+use cycles_numerologie_du_tarot::donnees::exporter_csv;
+#[cfg(feature = "parquet")]
+use cycles_numerologie_du_tarot::donnees::exporter_parquet;
+use cycles_numerologie_du_tarot::export::{encoder_pdf_avec_texte, encoder_svg_matriciel};
+use cycles_numerologie_du_tarot::ical::{ical, OptionsIcal};
+use cycles_numerologie_du_tarot::personnel::CyclesSections;
+use cycles_numerologie_du_tarot::rapport::{rendre_rapport, ContexteRapport, FormatRapport};
+use cycles_numerologie_du_tarot::recherche::{rechercher, Contrainte};
+use cycles_numerologie_du_tarot::statistiques::{statistiques, Ponderation};
+use cycles_numerologie_du_tarot::groupe::{constellation, rendre_constellation, Membre};
+use cycles_numerologie_du_tarot::template::encoder_png;
+use cycles_numerologie_du_tarot::nom::ids_calques_nom;
+use cycles_numerologie_du_tarot::tableau::NOMS_POSITIONS;
+use cycles_numerologie_du_tarot::{calcul, chronologie, compatibility, cycles_entre, numerologie_nom, Accent, Couche, Effet, OptionsRendu};
+use cycles_numerologie_du_tarot::{analyser_langue, lecture_langue, Analyse, Groupement, Interpretations, Lecture};
+use cycles_numerologie_du_tarot::langue::{message, nom_etape};
+use cycles_numerologie_du_tarot::{message_erreur, Erreur, Langue, Localise};
+use cycles_numerologie_du_tarot::{Date, Definitions, Evaluation, NumerologieNom, Position, TableLettres, Tableau, Template, VisibilityPolicy};
+use serde::Serialize;
+
+/********************************************
+ * cycles: calcul et rendu des cycles de    *
+ * numérologie du tarot en ligne de commande *
+ ********************************************/
+#[derive(Parser)]
+#[command(name = "cycles", version, about = "Cycles de numérologie du tarot")]
+struct Cli {
+    /// Langue des rapports et des erreurs (fr, en)
+    #[arg(long, global = true, env = "CYCLES_LANGUE", default_value = "fr")]
+    langue: Langue,
+    #[command(subcommand)]
+    commande: Commande,
+}
+
+#[derive(Subcommand)]
+enum Commande {
+    /// Calcule le tableau et l'affiche (table ou JSON)
+    Compute {
+        #[command(flatten)]
+        dates: Dates,
+        #[command(flatten)]
+        nom: NomArgs,
+        #[command(flatten)]
+        analyse: AnalyseArgs,
+        #[command(flatten)]
+        textes: TextesArgs,
+        #[command(flatten)]
+        positions: PositionsArgs,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Compose le PNG à partir du template PSD
+    Render {
+        #[command(flatten)]
+        dates: Dates,
+        #[command(flatten)]
+        nom: NomArgs,
+        #[command(flatten)]
+        analyse: AnalyseArgs,
+        #[command(flatten)]
+        textes: TextesArgs,
+        #[command(flatten)]
+        template: TemplateArgs,
+        #[command(flatten)]
+        positions: PositionsArgs,
+        #[command(flatten)]
+        rendu: RenduArgs,
+        /// Fichier de sortie ("-" ou absent: sortie standard)
+        #[arg(short, long)]
+        sortie: Option<String>,
+        /// Format (par défaut: d'après l'extension de la sortie, sinon PNG); le SVG enveloppe le PNG (pas de tracé vectoriel), le PDF contient l'analyse et la lecture
+        #[arg(long, value_enum)]
+        format: Option<FormatImage>,
+    },
+    /// Rapport de lecture HTML ou Markdown
+    Report {
+        #[command(flatten)]
+        dates: Dates,
+        #[command(flatten)]
+        analyse: AnalyseArgs,
+        #[command(flatten)]
+        textes: TextesArgs,
+        #[command(flatten)]
+        template: TemplateFacultatif,
+        /// Image en SVG intégré plutôt qu'en PNG (HTML), PNG enveloppé sans tracé vectoriel
+        #[arg(long, requires = "psd")]
+        svg: bool,
+        /// Modèle minijinja remplaçant le modèle par défaut
+        #[arg(long)]
+        modele: Option<String>,
+        /// Format (par défaut: d'après l'extension de la sortie, sinon HTML)
+        #[arg(long, value_enum)]
+        format: Option<FormatRapportArg>,
+        /// Fichier de sortie ("-" ou absent: sortie standard)
+        #[arg(short, long)]
+        sortie: Option<String>,
+    },
+    /// Cycles annuels sur une plage d'âges
+    Timeline {
+        #[command(flatten)]
+        dates: Dates,
+        /// Premier âge (par défaut: âge au jour de lecture)
+        #[arg(long)]
+        de: Option<usize>,
+        /// Dernier âge (par défaut: premier âge + 9)
+        #[arg(long)]
+        a: Option<usize>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Animation du tableau au fil des âges, composée à partir du template PSD
+    Animate {
+        #[command(flatten)]
+        dates: Dates,
+        #[command(flatten)]
+        template: TemplateArgs,
+        #[command(flatten)]
+        positions: PositionsArgs,
+        #[command(flatten)]
+        rendu: RenduArgs,
+        /// Premier âge (par défaut: âge au jour de lecture)
+        #[arg(long)]
+        de: Option<usize>,
+        /// Dernier âge (par défaut: premier âge + 9)
+        #[arg(long)]
+        a: Option<usize>,
+        /// Durée de chaque image, en millisecondes
+        #[arg(long, default_value_t = 1000)]
+        duree: u32,
+        /// Fichier de sortie ("-" ou absent: sortie standard)
+        #[arg(short, long)]
+        sortie: Option<String>,
+        /// Format (par défaut: d'après l'extension de la sortie, sinon GIF)
+        #[arg(long, value_enum)]
+        format: Option<FormatAnimationArg>,
+    },
+    /// Cycles personnels du mois et du jour, jour par jour
+    Personal {
+        /// Date de naissance (JJ.MM.AAAA ou AAAA-MM-JJ)
+        naissance: Date,
+        /// Premier jour (par défaut: aujourd'hui)
+        #[arg(long)]
+        du: Option<Date>,
+        /// Dernier jour (par défaut: premier jour)
+        #[arg(long)]
+        au: Option<Date>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Calendrier iCalendar des changements de cycle
+    Ical {
+        /// Date de naissance (JJ.MM.AAAA ou AAAA-MM-JJ)
+        naissance: Date,
+        /// Première année civile
+        #[arg(long)]
+        de: usize,
+        /// Dernière année civile (par défaut: la première)
+        #[arg(long)]
+        a: Option<usize>,
+        /// Ajoute un événement par mois personnel
+        #[arg(long)]
+        mois: bool,
+        /// Nom ajouté au titre des événements
+        #[arg(long)]
+        nom: Option<String>,
+        /// Fichier .ics de sortie ("-" ou absent: sortie standard)
+        #[arg(short, long)]
+        sortie: Option<String>,
+    },
+    /// Dates de naissance donnant une configuration, ex: "PPR.PA = 19" "NEM.PA in {7, 16}"
+    Search {
+        /// Contraintes: POSITION.VARIANTE (= N | != N | in {N, ..} | present | absent)
+        #[arg(required = true)]
+        contraintes: Vec<Contrainte>,
+        /// Première date de naissance
+        #[arg(long, default_value = "1900-01-01")]
+        du: Date,
+        /// Dernière date de naissance
+        #[arg(long, default_value = "2100-12-31")]
+        au: Date,
+        /// Âge pour les positions du cycle (PULSION_PPR, ...)
+        #[arg(long, default_value_t = 0)]
+        age: usize,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Fréquence des arcanes par position et variante sur une plage de naissances
+    Stats {
+        /// Première date de naissance
+        #[arg(long, default_value = "1900-01-01")]
+        du: Date,
+        /// Dernière date de naissance
+        #[arg(long, default_value = "2100-12-31")]
+        au: Date,
+        /// Âge pour les positions du cycle (PULSION_PPR, ...)
+        #[arg(long, default_value_t = 0)]
+        age: usize,
+        /// Table de natalité CSV (annee[,mois[,jour]],poids)
+        #[arg(long)]
+        poids: Option<String>,
+        /// Date de naissance dont on veut la rareté, à l'âge demandé
+        #[arg(long)]
+        rarete: Option<Date>,
+        #[arg(long, value_enum, default_value_t = FormatExport::Table)]
+        format: FormatExport,
+    },
+    /// Exporte le tableau complet de chaque date de naissance d'une plage (CSV ou Parquet)
+    Dataset {
+        /// Première date de naissance
+        #[arg(long, default_value = "1900-01-01")]
+        du: Date,
+        /// Dernière date de naissance
+        #[arg(long, default_value = "2100-12-31")]
+        au: Date,
+        /// Âge pour les positions du cycle (PULSION_PPR, ...)
+        #[arg(long, default_value_t = 0)]
+        age: usize,
+        /// Format (par défaut: d'après l'extension de la sortie, sinon CSV); Parquet nécessite --sortie et un binaire compilé avec la feature "parquet" (absente par défaut)
+        #[arg(long, value_enum)]
+        format: Option<FormatDonnees>,
+        /// Fichier de sortie ("-" ou absent: sortie standard, CSV uniquement)
+        #[arg(short, long)]
+        sortie: Option<String>,
+    },
+    /// Tableau de couple (A, B et tableau commun)
+    Couple {
+        /// Date de naissance de A
+        naissance_a: Date,
+        /// Date de naissance de B
+        naissance_b: Date,
+        /// Date de lecture (par défaut: aujourd'hui)
+        #[arg(short, long)]
+        lecture: Option<Date>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+        /// PNG côte à côte (A | couple | B), nécessite --psd
+        #[arg(short, long, requires = "psd")]
+        sortie: Option<String>,
+        #[command(flatten)]
+        template: TemplateFacultatif,
+    },
+    /// Constellation d'un groupe ou d'une famille
+    Group {
+        /// Membres: DATE ou NOM=DATE
+        #[arg(required = true, num_args = 2..)]
+        membres: Vec<String>,
+        /// Date de lecture (par défaut: aujourd'hui)
+        #[arg(short, long)]
+        lecture: Option<Date>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+        /// PNG des tableaux (membres puis groupe), nécessite --psd
+        #[arg(short, long, requires = "psd")]
+        sortie: Option<String>,
+        /// Nombre de tableaux par ligne dans le PNG
+        #[arg(long, default_value_t = 4)]
+        colonnes: usize,
+        #[command(flatten)]
+        template: TemplateFacultatif,
+    },
+    /// Rend les tableaux de tous les clients d'un CSV (nom,jour,mois,annee[,age][,lecture])
+    Batch {
+        /// Fichier CSV des clients
+        csv: String,
+        #[command(flatten)]
+        template: TemplateArgs,
+        /// Dossier de sortie (PNG, resume.json, resume.csv)
+        #[arg(short, long)]
+        sortie: String,
+        /// Modèle de nom: {ligne} {nom} {jour} {mois} {annee} {age}
+        #[arg(long)]
+        noms: Option<String>,
+        /// Date de lecture par défaut (sinon aujourd'hui)
+        #[arg(short, long)]
+        lecture: Option<Date>,
+        /// Reprend un lot interrompu, sans refaire les lignes terminées
+        #[arg(long)]
+        reprendre: bool,
+    },
+    /// Vérifie que le template PSD contient tous les calques nécessaires
+    ValidateTemplate {
+        #[command(flatten)]
+        template: TemplateArgs,
+    },
+}
+
+#[derive(Args)]
+struct Dates {
+    /// Date de naissance (JJ.MM.AAAA ou AAAA-MM-JJ)
+    naissance: Date,
+    /// Date de lecture (par défaut: aujourd'hui)
+    #[arg(short, long)]
+    lecture: Option<Date>,
+    /// Âge révolu (150 au plus), remplace la date de lecture
+    #[arg(long, conflicts_with = "lecture")]
+    age: Option<usize>,
+}
+
+#[derive(Args)]
+struct NomArgs {
+    /// Prénom(s), ajoute les positions du nom
+    #[arg(long)]
+    prenom: Option<String>,
+    /// Nom de famille
+    #[arg(long)]
+    nom: Option<String>,
+    /// Table lettres -> nombres
+    #[arg(long, value_enum, default_value_t = Lettres::Pythagoricienne)]
+    lettres: Lettres,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Lettres {
+    Pythagoricienne,
+    Chaldeenne,
+    Alphabetique,
+}
+
+impl NomArgs {
+    fn numerologie(&self) -> Option<NumerologieNom> {
+        if self.prenom.is_none() && self.nom.is_none() {
+            return None;
+        }
+        let table = match self.lettres {
+            Lettres::Pythagoricienne => TableLettres::pythagoricienne(),
+            Lettres::Chaldeenne => TableLettres::chaldeenne(),
+            Lettres::Alphabetique => TableLettres::alphabetique(),
+        };
+        Some(numerologie_nom(self.prenom.as_deref().unwrap_or(""), self.nom.as_deref().unwrap_or(""), &table))
+    }
+}
+
+#[derive(Args)]
+struct AnalyseArgs {
+    /// Regroupement d'arcanes en plus de parité et actif/réceptif, ex: "Éléments:Feu=1,7;Eau=2,18"
+    #[arg(long = "groupement")]
+    groupements: Vec<Groupement>,
+}
+
+impl AnalyseArgs {
+    fn groupements(&self, langue: Langue) -> Vec<Groupement> {
+        let mut groupements = Groupement::par_defaut_langue(langue);
+        groupements.extend(self.groupements.iter().cloned());
+        groupements
+    }
+
+    fn analyse(&self, tableau: &Tableau, langue: Langue) -> Analyse {
+        analyser_langue(tableau, &self.groupements(langue), langue)
+    }
+}
+
+#[derive(Args)]
+struct TextesArgs {
+    /// Textes d'interprétation (.toml, .yaml ou .yml), ajoute la lecture
+    #[arg(long)]
+    textes: Option<String>,
+}
+
+impl TextesArgs {
+    fn lecture(&self, tableau: &Tableau, langue: Langue) -> Result<Option<Lecture>, Box<dyn Error>> {
+        match self.textes.as_deref() {
+            Some(path) => Ok(Some(lecture_langue(tableau, &Interpretations::ouvrir(path)?, langue))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Args)]
+struct PositionsArgs {
+    /// Table des positions (.toml ou .ron) remplaçant la table intégrée, voir exemples/positions.toml
+    #[arg(long)]
+    positions: Option<String>,
+    /// Variantes visibles: règles de la table, toutes (même identiques) ou principales (PA, PB)
+    #[arg(long, value_enum, default_value_t = Visibilite::Regles)]
+    visibilite: Visibilite,
+}
+
+impl PositionsArgs {
+    fn definitions(&self) -> Result<Option<Definitions>, Box<dyn Error>> {
+        match self.positions.as_deref() {
+            Some(path) => Ok(Some(ouvrir_definitions(path)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn politique(&self) -> VisibilityPolicy {
+        match self.visibilite {
+            Visibilite::Regles => VisibilityPolicy::Regles,
+            Visibilite::Toutes => VisibilityPolicy::Toutes,
+            Visibilite::Principales => VisibilityPolicy::Principales,
+        }
+    }
+
+    // Évaluation de la table chargée et tableau, politique de visibilité appliquée
+    fn calculer<'a>(&self, definitions: Option<&'a Definitions>, n: &Date, age: usize) -> Result<(Option<Evaluation<'a>>, Tableau), Box<dyn Error>> {
+        let politique = self.politique();
+        match definitions {
+            Some(d) => {
+                let evaluation = politique.evaluation(d.evaluer(n.jour, n.mois, n.annee, age)?);
+                let tableau = evaluation.tableau()?;
+                Ok((Some(evaluation), tableau))
+            },
+            None => Ok((None, politique.tableau(&calcul(n.jour, n.mois, n.annee, age)))),
+        }
+    }
+}
+
+#[derive(Args)]
+struct RenduArgs {
+    /// Sections dessinées, dans l'ordre d'empilement (ex: PPR,NEM), toutes par défaut
+    #[arg(long, value_delimiter = ',')]
+    sections: Vec<String>,
+    /// Couches dessinées, dans l'ordre d'empilement (la première dessous)
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [CoucheArg::CadresB, CoucheArg::ValeursB, CoucheArg::CadresA, CoucheArg::ValeursA])]
+    couches: Vec<CoucheArg>,
+    /// Chaque section entière (toutes ses couches) avant la suivante
+    #[arg(long)]
+    par_section: bool,
+    /// Une image par section: tableau.png -> tableau-PPR.png, tableau-NEM.png...
+    #[arg(long)]
+    separees: bool,
+    /// Met en valeur le cycle de l'année en cours
+    #[arg(long, value_enum)]
+    accent: Option<AccentArg>,
+    /// Couleur de la teinte ou du halo (RRGGBB)
+    #[arg(long, default_value = "ffb000", requires = "accent")]
+    couleur: String,
+    /// Cycles de l'année précédente et suivante en transparence
+    #[arg(long, requires = "accent")]
+    voisins: bool,
+}
+
+impl RenduArgs {
+    // Politique déjà appliquée au tableau: reprise pour les cycles voisins
+    fn options(&self, visibilite: VisibilityPolicy) -> Result<OptionsRendu, Erreur> {
+        let couleur = couleur_hex(&self.couleur)?;
+        let accent = self.accent.map(|a| Accent {
+            effet: match a {
+                AccentArg::Teinte => Effet::Teinte { couleur, force: 0.6 },
+                AccentArg::Halo => Effet::Halo { couleur, rayon: 12 },
+                AccentArg::Attenuation => Effet::Attenuation(0.35),
+            },
+            voisins: self.voisins.then_some(0.25),
+        });
+        let couches = self.couches.iter().map(|c| match c {
+            CoucheArg::CadresB => Couche::CadresB,
+            CoucheArg::ValeursB => Couche::ValeursB,
+            CoucheArg::CadresA => Couche::CadresA,
+            CoucheArg::ValeursA => Couche::ValeursA,
+        });
+        Ok(OptionsRendu { visibilite, sections: self.sections.clone(), couches: couches.collect(), par_section: self.par_section, accent })
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AccentArg {
+    Teinte,
+    Halo,
+    Attenuation,
+}
+
+// "ffb000" ou "#ffb000" -> [255, 176, 0]
+fn couleur_hex(texte: &str) -> Result<[u8; 3], Erreur> {
+    let hex = texte.trim_start_matches('#');
+    let composante = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+    match (hex.len(), composante(0), composante(2), composante(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok([r, g, b]),
+        _ => Err(Erreur::new("erreur-couleur", &[("couleur", texte.to_string())])),
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CoucheArg {
+    CadresB,
+    ValeursB,
+    CadresA,
+    ValeursA,
+}
+
+// tableau.png -> tableau-PPR.png
+fn fichier_section(sortie: &str, section: &str) -> String {
+    let chemin = Path::new(sortie);
+    let base = chemin.file_stem().and_then(|s| s.to_str()).unwrap_or(sortie);
+    let nom = match chemin.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}-{}.{}", base, section, ext),
+        None => format!("{}-{}", base, section),
+    };
+    chemin.with_file_name(nom).to_string_lossy().into_owned()
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Visibilite {
+    Regles,
+    Toutes,
+    Principales,
+}
+
+// Tableau, analyse et, si demandé, positions du nom et lecture dans le même objet JSON
+#[derive(Serialize)]
+struct SortieTableau {
+    #[serde(flatten)]
+    tableau: Tableau,
+    // Positions ajoutées par une table chargée
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    autres: BTreeMap<String, Position>,
+    analyse: Analyse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nom: Option<NumerologieNom>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lecture: Option<Lecture>,
+}
+
+#[derive(Args)]
+struct TemplateArgs {
+    /// Chemin du template PSD
+    #[arg(long, env = "CYCLES_PSD")]
+    psd: String,
+    /// Nommage des calques du template (.toml ou .ron), voir exemples/nommage.toml
+    #[arg(long, env = "CYCLES_NOMMAGE")]
+    nommage: Option<String>,
+}
+
+impl TemplateArgs {
+    fn ouvrir(&self) -> Result<Template, Box<dyn Error>> {
+        let template = Template::ouvrir(&self.psd)?;
+        match self.nommage.as_deref() {
+            Some(path) => Ok(template.avec_nommage(ouvrir_nommage(path)?)),
+            None => Ok(template),
+        }
+    }
+}
+
+// Template facultatif (image d'un rapport, d'un couple, d'un groupe)
+#[derive(Args)]
+struct TemplateFacultatif {
+    /// Template PSD, ajoute l'image
+    #[arg(long, env = "CYCLES_PSD")]
+    psd: Option<String>,
+    /// Nommage des calques du template (.toml ou .ron), voir exemples/nommage.toml
+    #[arg(long, env = "CYCLES_NOMMAGE", requires = "psd")]
+    nommage: Option<String>,
+}
+
+impl TemplateFacultatif {
+    fn ouvrir(self) -> Result<Option<Template>, Box<dyn Error>> {
+        self.psd.map(|psd| TemplateArgs { psd, nommage: self.nommage }.ouvrir()).transpose()
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FormatExport {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum FormatImage {
+    Png,
+    Svg,
+    Pdf,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum FormatAnimationArg {
+    Gif,
+    Apng,
+    Webp,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum FormatRapportArg {
+    Html,
+    Markdown,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum FormatDonnees {
+    Csv,
+    /// Binaire compilé avec la feature "parquet" seulement
+    Parquet,
+}
+
+impl Dates {
+    fn age(&self) -> Result<usize, Box<dyn Error>> {
+        if let Some(age) = self.age {
+            return Ok(verifier_age(age)?);
+        }
+        let lecture = self.lecture.unwrap_or_else(Date::aujourd_hui);
+        Ok(self.naissance.age_le(&lecture)?)
+    }
+
+    // --de (par défaut: âge à la lecture) à --a (par défaut: premier âge + 9)
+    fn ages(&self, de: Option<usize>, a: Option<usize>) -> Result<RangeInclusive<usize>, Box<dyn Error>> {
+        let de = match de {
+            Some(de) => verifier_age(de)?,
+            None => self.age()?,
+        };
+        Ok(plage_ages(de, a.unwrap_or((de + 9).min(AGE_MAX)))?)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => return erreur_arguments(e),
+    };
+    let langue = cli.langue;
+    let mut out = std::io::stdout().lock();
+    match executer(cli, &mut out).and_then(|code| Ok(out.flush().map(|_| code)?)) {
+        Ok(code) => code,
+        // Sortie fermée par le lecteur (cycles search ... | head): arrêt normal
+        Err(e) if e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == ErrorKind::BrokenPipe) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", message(langue, "erreur", &[("message", message_erreur(e.as_ref(), langue))]));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/*****************************************************
+ * Valeur d'argument refusée (date, groupement,      *
+ * contrainte...): message traduit, code 2 comme     *
+ * clap. Les autres cas (aide, version, argument     *
+ * inconnu) restent ceux de clap, en français        *
+ *****************************************************/
+fn erreur_arguments(e: clap::Error) -> ExitCode {
+    let langue = langue_demandee();
+    match (e.kind(), e.get(ContextKind::InvalidArg), e.source()) {
+        (ErreurClap::ValueValidation, Some(ContextValue::String(argument)), Some(source)) => {
+            let arguments = [("argument", argument.clone()), ("message", message_erreur(source, langue))];
+            eprintln!("{}", message(langue, "erreur", &[("message", message(langue, "erreur-argument", &arguments))]));
+            ExitCode::from(2)
+        },
+        _ => e.exit(),
+    }
+}
+
+// --langue lue avant clap, sinon CYCLES_LANGUE
+fn langue_demandee() -> Langue {
+    let args: Vec<String> = std::env::args().collect();
+    let valeur = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .find_map(|(a, b)| (a == "--langue").then(|| b.clone()))
+        .or_else(|| args.iter().find_map(|a| a.strip_prefix("--langue=").map(|l| l.to_string())))
+        .or_else(|| std::env::var("CYCLES_LANGUE").ok());
+    valeur.and_then(|l| l.parse().ok()).unwrap_or_default()
+}
+
+fn executer(cli: Cli, out: &mut impl Write) -> Result<ExitCode, Box<dyn Error>> {
+    let langue = cli.langue;
+    match cli.commande {
+        Commande::Compute { dates, nom, analyse, textes, positions, format } => {
+            let n = dates.naissance;
+            let definitions = positions.definitions()?;
+            let (evaluation, tableau) = positions.calculer(definitions.as_ref(), &n, dates.age()?)?;
+            let nom = nom.numerologie();
+            let analyse = analyse.analyse(&tableau, langue);
+            let lecture = textes.lecture(&tableau, langue)?;
+            match format {
+                Format::Table => {
+                    match evaluation.as_ref() {
+                        Some(e) => write!(out, "{}", e)?,
+                        None => write!(out, "{}", tableau.dans(langue))?,
+                    }
+                    if let Some(nom) = nom {
+                        write!(out, "{}", nom.dans(langue))?;
+                    }
+                    write!(out, "{}", analyse.dans(langue))?;
+                    if let Some(lecture) = lecture {
+                        write!(out, "{}", lecture)?;
+                    }
+                },
+                Format::Json => {
+                    let autres = evaluation
+                        .iter()
+                        .flat_map(|e| e.positions.iter())
+                        .filter(|(d, _)| !NOMS_POSITIONS.contains(&d.nom.as_str()))
+                        .map(|(d, p)| (d.nom.clone(), *p))
+                        .collect();
+                    writeln!(out, "{}", serde_json::to_string_pretty(&SortieTableau { tableau, autres, analyse, nom, lecture })?)?
+                },
+            }
+        },
+        Commande::Render { dates, nom, analyse, textes, template, positions, rendu, sortie, format } => {
+            let n = dates.naissance;
+            let format = format.unwrap_or(match sortie.as_deref() {
+                Some(s) if s.ends_with(".pdf") => FormatImage::Pdf,
+                Some(s) if s.ends_with(".svg") => FormatImage::Svg,
+                _ => FormatImage::Png,
+            });
+            let options = rendu.options(positions.politique())?;
+            let definitions = positions.definitions()?;
+            match definitions.as_ref() {
+                Some(d) => options.verifier(d)?,
+                None => options.verifier(&Definitions::integree())?,
+            }
+            if rendu.separees {
+                let sortie = sortie.as_deref().filter(|s| *s != "-").ok_or_else(|| Erreur::new("erreur-separees-sortie", &[]))?;
+                let (evaluation, tableau) = positions.calculer(definitions.as_ref(), &n, dates.age()?)?;
+                let template = template.ouvrir()?.avec_options(options);
+                let images = match evaluation.as_ref() {
+                    Some(e) => template.images_sections_evaluation(e)?,
+                    None => template.images_sections(&tableau)?,
+                };
+                for (section, image) in images.iter() {
+                    let octets = match format {
+                        FormatImage::Png => encoder_png(image)?,
+                        FormatImage::Svg => encoder_svg_matriciel(image)?,
+                        FormatImage::Pdf => return Err(Erreur::new("erreur-separees-format", &[]).into()),
+                    };
+                    let fichier = fichier_section(sortie, section);
+                    fs::write(&fichier, &octets)
+                        .map_err(|e| erreur_ecriture(&fichier, e))?;
+                    writeln!(out, "{}: {}", section, fichier)?;
+                }
+                return Ok(ExitCode::SUCCESS);
+            }
+            let octets = match (nom.numerologie(), format, definitions) {
+                (None, FormatImage::Png, None) => {
+                    let template = template.ouvrir()?.avec_options(options);
+                    encoder_png(&template.image(n.jour, n.mois, n.annee, dates.age()?)?)?
+                },
+                (nom, format, definitions) => {
+                    let (evaluation, tableau) = positions.calculer(definitions.as_ref(), &n, dates.age()?)?;
+                    let mut c = match evaluation.as_ref() {
+                        Some(e) => options.calques_evaluation(e),
+                        None => options.calques_tableau(&tableau),
+                    };
+                    if let Some(nom) = nom.as_ref() {
+                        c.extend(ids_calques_nom(nom, &options));
+                    }
+                    let template = template.ouvrir()?.avec_options(options);
+                    let image = match evaluation.as_ref() {
+                        Some(e) => template.image_calques_evaluation(c, e)?,
+                        None => template.image_calques_tableau(c, &tableau)?,
+                    };
+                    match format {
+                        FormatImage::Png => encoder_png(&image)?,
+                        FormatImage::Svg => encoder_svg_matriciel(&image)?,
+                        FormatImage::Pdf => {
+                            let mut texte = tableau.dans(langue).to_string();
+                            if let Some(nom) = nom {
+                                texte.push_str(&nom.dans(langue).to_string());
+                            }
+                            texte.push_str(&analyse.analyse(&tableau, langue).dans(langue).to_string());
+                            if let Some(lecture) = textes.lecture(&tableau, langue)? {
+                                texte.push_str(&lecture.to_string());
+                            }
+                            encoder_pdf_avec_texte(&image, &texte)?
+                        },
+                    }
+                },
+            };
+            match sortie.as_deref() {
+                None | Some("-") => out.write_all(&octets)?,
+                Some(fichier) => fs::write(fichier, &octets)
+                    .map_err(|e| erreur_ecriture(fichier, e))?,
+            }
+        },
+        Commande::Report { dates, analyse, textes, template, svg, modele, format, sortie } => {
+            let n = dates.naissance;
+            let tableau = calcul(n.jour, n.mois, n.annee, dates.age()?);
+            let format = match format.unwrap_or(match sortie.as_deref() {
+                Some(s) if s.ends_with(".md") || s.ends_with(".markdown") => FormatRapportArg::Markdown,
+                _ => FormatRapportArg::Html,
+            }) {
+                FormatRapportArg::Html => FormatRapport::Html,
+                FormatRapportArg::Markdown => FormatRapport::Markdown,
+            };
+            let mut contexte = ContexteRapport::avec_groupements(&tableau, &analyse.groupements(langue), langue);
+            if let Some(lecture) = textes.lecture(&tableau, langue)? {
+                contexte = contexte.lecture(lecture);
+            }
+            if let Some(template) = template.ouvrir()? {
+                let image = template.image_tableau(&tableau)?;
+                contexte = if svg { contexte.image_svg(&image)? } else { contexte.image_png(&image)? };
+            }
+            let modele = match modele {
+                Some(fichier) => Some(fs::read_to_string(&fichier).map_err(|e| erreur_lecture(&fichier, e))?),
+                None => None,
+            };
+            let rapport = rendre_rapport(&contexte, format, modele.as_deref())?;
+            match sortie.as_deref() {
+                None | Some("-") => write!(out, "{}", rapport)?,
+                Some(fichier) => fs::write(fichier, rapport)
+                    .map_err(|e| erreur_ecriture(fichier, e))?,
+            }
+        },
+        Commande::Timeline { dates, de, a, format } => {
+            let n = dates.naissance;
+            let tableaux = chronologie(n.jour, n.mois, n.annee, dates.ages(de, a)?);
+            match format {
+                Format::Table => {
+                    let actions = |section: &str| message(langue, "colonne-actions", &[("section", section.to_string())]);
+                    writeln!(
+                        out,
+                        "{:>4} {:>6}  {:<8} {:<20} {:<20} {:<20}",
+                        message(langue, "libelle-age", &[]),
+                        message(langue, "colonne-annee", &[]),
+                        nom_etape("pulsion", langue),
+                        actions("PPR"),
+                        actions("NEM"),
+                        actions("PEX")
+                    )?;
+                    for t in tableaux.iter() {
+                        writeln!(
+                            out,
+                            "{:>4} {:>6}  {:<8} {:<20} {:<20} {:<20}",
+                            t.age,
+                            n.annee + t.age,
+                            visibles(&t.cycle_ppr.pulsion),
+                            format!("{} | {}", visibles(&t.cycle_ppr.action), visibles(&t.cycle_ppr.reaction)),
+                            format!("{} | {}", visibles(&t.cycle_nem.action), visibles(&t.cycle_nem.reaction)),
+                            format!("{} | {}", visibles(&t.cycle_pex.action), visibles(&t.cycle_pex.reaction)),
+                        )?;
+                    }
+                },
+                Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(&tableaux)?)?,
+            }
+        },
+        Commande::Animate { dates, template, positions, rendu, de, a, duree, sortie, format } => {
+            let n = dates.naissance;
+            let ages = dates.ages(de, a)?;
+            if rendu.separees {
+                return Err(Erreur::new("erreur-separees-animation", &[]).into());
+            }
+            let format = match format.unwrap_or(match sortie.as_deref() {
+                Some(s) if s.ends_with(".png") || s.ends_with(".apng") => FormatAnimationArg::Apng,
+                Some(s) if s.ends_with(".webp") => FormatAnimationArg::Webp,
+                _ => FormatAnimationArg::Gif,
+            }) {
+                FormatAnimationArg::Gif => FormatAnimation::Gif,
+                FormatAnimationArg::Apng => FormatAnimation::Apng,
+                FormatAnimationArg::Webp => FormatAnimation::Webp,
+            };
+            let options = rendu.options(positions.politique())?;
+            let definitions = positions.definitions()?;
+            match definitions.as_ref() {
+                Some(d) => options.verifier(d)?,
+                None => options.verifier(&Definitions::integree())?,
+            }
+            let template = template.ouvrir()?.avec_options(options);
+            let images = match definitions.as_ref() {
+                Some(d) => template.images_ages_evaluation(d, n.jour, n.mois, n.annee, ages)?,
+                None => template.images_ages(n.jour, n.mois, n.annee, ages)?,
+            };
+            let octets = encoder_animation(&images, duree, format)?;
+            match sortie.as_deref() {
+                None | Some("-") => out.write_all(&octets)?,
+                Some(fichier) => fs::write(fichier, &octets)
+                    .map_err(|e| erreur_ecriture(fichier, e))?,
+            }
+        },
+        Commande::Personal { naissance, du, au, format } => {
+            let du = du.unwrap_or_else(Date::aujourd_hui);
+            let au = au.unwrap_or(du);
+            verifier_age(naissance.age_au(&au).unwrap_or(0))?;
+            let jours: Vec<_> = cycles_entre(&naissance, &du, &au).collect();
+            match format {
+                Format::Table => {
+                    writeln!(
+                        out,
+                        "{:<10} {:>4}  {:<24} {:<24} {:<24}",
+                        message(langue, "colonne-date", &[]),
+                        message(langue, "libelle-age", &[]),
+                        message(langue, "colonne-cycle-annee", &[]),
+                        message(langue, "colonne-mois", &[]),
+                        message(langue, "colonne-jour", &[])
+                    )?;
+                    for c in jours.iter() {
+                        let resume = |s: &CyclesSections| format!(
+                            "{} / {} / {}",
+                            visibles(&s.ppr.pulsion),
+                            visibles(&s.ppr.action),
+                            visibles(&s.ppr.reaction)
+                        );
+                        writeln!(out, "{:<10} {:>4}  {:<24} {:<24} {:<24}", c.date, c.age, resume(&c.annee), resume(&c.mois), resume(&c.jour))?;
+                    }
+                },
+                Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(&jours)?)?,
+            }
+        },
+        Commande::Ical { naissance, de, a, mois, nom, sortie } => {
+            let a = a.unwrap_or(de);
+            if a < de {
+                return Err(ErreurDate::PlageAnnees { de, a }.into());
+            }
+            verifier_age(a.saturating_sub(naissance.annee))?;
+            let ics = ical(&naissance, &OptionsIcal { annees: de..=a, mois_personnels: mois, nom, langue });
+            match sortie.as_deref() {
+                None | Some("-") => write!(out, "{}", ics)?,
+                Some(fichier) => fs::write(fichier, ics)
+                    .map_err(|e| erreur_ecriture(fichier, e))?,
+            }
+        },
+        Commande::Search { contraintes, du, au, age, format } => {
+            let dates = rechercher(&contraintes, &du, &au, verifier_age(age)?);
+            match format {
+                Format::Table => {
+                    for date in dates.iter() {
+                        writeln!(out, "{}", date)?;
+                    }
+                    eprintln!("{}", message(langue, "recherche-total", &[("nombre", dates.len().to_string())]));
+                },
+                Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(&dates)?)?,
+            }
+        },
+        Commande::Stats { du, au, age, poids, rarete, format } => {
+            let ponderation = match poids {
+                Some(fichier) => Some(Ponderation::depuis_csv(
+                    &fs::read_to_string(&fichier).map_err(|e| erreur_lecture(&fichier, e))?,
+                )?),
+                None => None,
+            };
+            let stats = statistiques(&du, &au, verifier_age(age)?, ponderation.as_ref());
+            let rarete = rarete.map(|n| (n, stats.rarete(&calcul(n.jour, n.mois, n.annee, stats.age))));
+            match format {
+                FormatExport::Table => {
+                    let arguments = [("du", du.to_string()), ("au", au.to_string()), ("age", stats.age.to_string()), ("poids", format!("{:.1}", stats.poids_total))];
+                    writeln!(out, "{}", message(langue, "stats-entete", &arguments))?;
+                    let present = message(langue, "stats-present", &[]);
+                    for p in stats.positions.iter() {
+                        for v in p.variantes.iter().filter(|v| v.presence > 0.0) {
+                            let frequences: Vec<String> = v.frequences
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, f)| **f > 0.0)
+                                .map(|(i, f)| format!("{}:{:.1}%", i + 1, f * 100.0))
+                                .collect();
+                            writeln!(out, "{:<12} {}  {} {:>5.1}%  {}", p.position, v.variante, present, v.presence * 100.0, frequences.join(" "))?;
+                        }
+                    }
+                    if let Some((n, r)) = rarete {
+                        writeln!(out, "{}", message(langue, "stats-rarete", &[("naissance", n.to_string()), ("percentile", format!("{:.2}", r))]))?;
+                    }
+                },
+                FormatExport::Json => {
+                    let mut json = serde_json::to_value(&stats)?;
+                    if let Some((n, r)) = rarete {
+                        json["rarete"] = serde_json::json!({ "naissance": n, "percentile": r });
+                    }
+                    writeln!(out, "{}", serde_json::to_string_pretty(&json)?)?;
+                },
+                FormatExport::Csv => {
+                    write!(out, "{}", stats.csv())?;
+                    if let Some((n, r)) = rarete {
+                        eprintln!("{}", message(langue, "stats-rarete", &[("naissance", n.to_string()), ("percentile", format!("{:.2}", r))]));
+                    }
+                },
+            }
+        },
+        Commande::Dataset { du, au, age, format, sortie } => {
+            let sortie = sortie.filter(|s| s != "-");
+            let format = format.unwrap_or(match sortie.as_deref() {
+                Some(s) if s.ends_with(".parquet") => FormatDonnees::Parquet,
+                _ => FormatDonnees::Csv,
+            });
+            // Avant de créer le fichier de sortie
+            if format == FormatDonnees::Parquet && !cfg!(feature = "parquet") {
+                return Err(Erreur::new("erreur-parquet-indisponible", &[]).into());
+            }
+            let age = verifier_age(age)?;
+            let lignes = match (format, sortie.as_deref()) {
+                (FormatDonnees::Csv, None) => exporter_csv(&du, &au, age, &mut *out)?,
+                (FormatDonnees::Parquet, None) => return Err(Erreur::new("erreur-parquet-sortie", &[]).into()),
+                (format, Some(fichier)) => {
+                    let f = fs::File::create(fichier).map_err(|e| erreur_ecriture(fichier, e))?;
+                    match format {
+                        FormatDonnees::Csv => exporter_csv(&du, &au, age, f)?,
+                        FormatDonnees::Parquet => exporter_parquet(&du, &au, age, f)?,
+                    }
+                },
+            };
+            eprintln!("{}", message(langue, "donnees-total", &[("nombre", lignes.to_string())]));
+        },
+        Commande::Couple { naissance_a, naissance_b, lecture, format, sortie, template } => {
+            let lecture = lecture.unwrap_or_else(Date::aujourd_hui);
+            let mut tableaux = vec![];
+            for n in [naissance_a, naissance_b] {
+                let age = n.age_le(&lecture)?;
+                tableaux.push(calcul(n.jour, n.mois, n.annee, age));
+            }
+            let c = compatibility(&tableaux[0], &tableaux[1]);
+            match format {
+                Format::Table => write!(out, "--- A ---\n{}--- B ---\n{}--- Couple ---\n{}", c.a.dans(langue), c.b.dans(langue), c.couple.dans(langue))?,
+                Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(&c)?)?,
+            }
+            if let Some(sortie) = sortie
+                && let Some(template) = template.ouvrir()? {
+                let image = rendre_compatibilite(&template, &c)?;
+                fs::write(&sortie, encoder_png(&image)?)
+                    .map_err(|e| erreur_ecriture(&sortie, e))?;
+            }
+        },
+        Commande::Group { membres, lecture, format, sortie, colonnes, template } => {
+            let lecture = lecture.unwrap_or_else(Date::aujourd_hui);
+            let mut groupe = vec![];
+            for membre in membres.iter() {
+                let (nom, date) = membre.split_once('=').unwrap_or((membre, membre));
+                let n: Date = date.parse()?;
+                let age = n.age_le(&lecture)?;
+                groupe.push(Membre { nom: nom.to_string(), tableau: calcul(n.jour, n.mois, n.annee, age) });
+            }
+            let c = constellation(&groupe);
+            match format {
+                Format::Table => write!(out, "{}", c.dans(langue))?,
+                Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(&c)?)?,
+            }
+            if let Some(sortie) = sortie
+                && let Some(template) = template.ouvrir()? {
+                let image = rendre_constellation(&template, &c, colonnes)?;
+                fs::write(&sortie, encoder_png(&image)?)
+                    .map_err(|e| erreur_ecriture(&sortie, e))?;
+            }
+        },
+        Commande::Batch { csv, template, sortie, noms, lecture, reprendre } => {
+            let clients = lire_clients(&csv)?;
+            let template = template.ouvrir()?;
+            let mut options = OptionsLot::new(sortie);
+            if let Some(noms) = noms {
+                options.modele_nom = noms;
+            }
+            if let Some(lecture) = lecture {
+                options.lecture = lecture;
+            }
+            options.reprendre = reprendre;
+            options.langue = langue;
+            let resultats = rendre_lot(&template, &clients, &options)?;
+            let mut erreurs = 0;
+            for r in resultats.iter() {
+                let mut arguments = vec![("ligne", r.ligne.to_string()), ("nom", r.nom.clone()), ("fichier", r.fichier.clone())];
+                match (&r.erreur, r.repris) {
+                    (Some(e), _) => {
+                        erreurs += 1;
+                        arguments.push(("erreur", e.clone()));
+                        eprintln!("{}", message(langue, "lot-erreur", &arguments));
+                    },
+                    (None, true) => writeln!(out, "{}", message(langue, "lot-repris", &arguments))?,
+                    (None, false) => writeln!(out, "{}", message(langue, "lot-rendu", &arguments))?,
+                }
+            }
+            if erreurs > 0 {
+                return Ok(ExitCode::FAILURE);
+            }
+        },
+        Commande::ValidateTemplate { template } => {
+            let manquants = template.ouvrir()?.calques_manquants();
+            if !manquants.is_empty() {
+                for calque in manquants.iter() {
+                    writeln!(out, "{}", message(langue, "template-calque-manquant", &[("calque", calque.to_string())]))?;
+                }
+                return Ok(ExitCode::FAILURE);
+            }
+            writeln!(out, "{}", message(langue, "template-valide", &[]))?;
+        },
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn erreur_ecriture(fichier: &str, e: std::io::Error) -> Erreur {
+    Erreur::new("erreur-ecriture", &[("chemin", fichier.to_string())]).avec_cause(e)
+}
+
+fn erreur_lecture(fichier: &str, e: std::io::Error) -> Erreur {
+    Erreur::new("erreur-fichier-lecture", &[("chemin", fichier.to_string())]).avec_cause(e)
+}
+
+// Variantes visibles d'une position, ex: "12/3"
+fn visibles(p: &Position) -> String {
+    p.variantes()
+        .iter()
+        .filter_map(|(_, arcane)| arcane.visible())
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(not(feature = "parquet"))]
+fn exporter_parquet(_: &Date, _: &Date, _: usize, _: fs::File) -> Result<usize, Box<dyn Error>> {
+    Err(Erreur::new("erreur-parquet-indisponible", &[]).into())
+}
@@ -0,0 +1,34 @@
+use clap::Parser;
+use cycles_numerologie_du_tarot::langue::message;
+use cycles_numerologie_du_tarot::serveur::application;
+use cycles_numerologie_du_tarot::{Erreur, Langue, Template};
+
+/*********************************************
+ * Service HTTP local: calcul et rendu des   *
+ * tableaux, le template PSD reste en mémoire *
+ *********************************************/
+#[derive(Parser)]
+#[command(name = "server", version, about = "Service HTTP des cycles de numérologie du tarot")]
+struct Options {
+    /// Chemin du template PSD (sans template, /render répond 503)
+    #[arg(long, env = "CYCLES_PSD")]
+    psd: Option<String>,
+    /// Adresse d'écoute
+    #[arg(long, env = "CYCLES_ADRESSE", default_value = "127.0.0.1:8080")]
+    adresse: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = Options::parse();
+    let template = match options.psd {
+        Some(path) => Some(Template::ouvrir(&path)?),
+        None => None,
+    };
+    let app = application(template);
+    let ecoute = tokio::net::TcpListener::bind(&options.adresse).await
+        .map_err(|e| Erreur::new("erreur-ecoute", &[("adresse", options.adresse.clone())]).avec_cause(e))?;
+    eprintln!("{}", message(Langue::default(), "serveur-ecoute", &[("adresse", options.adresse.clone())]));
+    axum::serve(ecoute, app).await?;
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+#[cfg(any(feature = "toml", feature = "ron"))]
+use crate::langue::Erreur;
+use crate::langue::{message, Langue};
+
+/*****************************************************
+ * Nom des calques Photoshop:                        *
+ * valeur {section}{variante}{arcane:02}, ex: APESB14 *
+ * cadre  {section}{variante}-R, ex: APESB-R          *
+ * section: préfixe de la table des positions        *
+ *****************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Genre {
+    Valeur,
+    Cadre,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VarianteCalque {
+    PA,
+    PB,
+    SA,
+    SB,
+}
+
+impl VarianteCalque {
+    pub const TOUTES: [VarianteCalque; 4] = [VarianteCalque::PA, VarianteCalque::PB, VarianteCalque::SA, VarianteCalque::SB];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            VarianteCalque::PA => "PA",
+            VarianteCalque::PB => "PB",
+            VarianteCalque::SA => "SA",
+            VarianteCalque::SB => "SB",
+        }
+    }
+
+    pub fn est_b(&self) -> bool {
+        matches!(self, VarianteCalque::PB | VarianteCalque::SB)
+    }
+}
+
+impl FromStr for VarianteCalque {
+    type Err = ErreurCalque;
+
+    fn from_str(s: &str) -> Result<VarianteCalque, ErreurCalque> {
+        VarianteCalque::TOUTES
+            .into_iter()
+            .find(|v| v.code() == s)
+            .ok_or_else(|| ErreurCalque::Variante { variante: s.to_string() })
+    }
+}
+
+/*****************************************************
+ * Erreurs des noms de calques, traduites par        *
+ * message(langue)                                   *
+ * Display: français                                 *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurCalque {
+    Invalide { calque: String },
+    Arcane { calque: String },
+    Variante { variante: String },
+    Ambigu { premier: String, second: String, calque: String },
+}
+
+impl ErreurCalque {
+    pub fn message(&self, langue: Langue) -> String {
+        match self {
+            ErreurCalque::Invalide { calque } => message(langue, "erreur-calque", &[("calque", calque.clone())]),
+            ErreurCalque::Arcane { calque } => message(langue, "erreur-calque-arcane", &[("calque", calque.clone())]),
+            ErreurCalque::Variante { variante } => message(langue, "erreur-variante", &[("variante", variante.clone())]),
+            ErreurCalque::Ambigu { premier, second, calque } => message(langue, "erreur-nommage-ambigu", &[
+                ("premier", premier.clone()),
+                ("second", second.clone()),
+                ("calque", calque.clone()),
+            ]),
+        }
+    }
+}
+
+impl fmt::Display for ErreurCalque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Langue::default()))
+    }
+}
+
+impl Error for ErreurCalque {}
+
+// Un cadre n'a pas d'arcane, une valeur a un arcane de 1 à 22
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LayerId {
+    pub section: String,
+    pub kind: Genre,
+    pub variant: VarianteCalque,
+    pub arcane: Option<usize>,
+}
+
+const SUFFIXE_CADRE: &str = "-R";
+
+impl LayerId {
+    pub fn valeur(section: &str, variant: VarianteCalque, arcane: usize) -> LayerId {
+        LayerId { section: section.to_string(), kind: Genre::Valeur, variant, arcane: Some(arcane) }
+    }
+
+    pub fn cadre_de(section: &str, variant: VarianteCalque) -> LayerId {
+        LayerId { section: section.to_string(), kind: Genre::Cadre, variant, arcane: None }
+    }
+
+    // Cadre dessiné derrière une valeur: même section, même variante
+    pub fn cadre(&self) -> Option<LayerId> {
+        match self.kind {
+            Genre::Valeur => Some(LayerId::cadre_de(&self.section, self.variant)),
+            Genre::Cadre => None,
+        }
+    }
+}
+
+impl fmt::Display for LayerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Genre::Valeur => write!(f, "{}{}{:02}", self.section, self.variant.code(), self.arcane.unwrap_or(0)),
+            Genre::Cadre => write!(f, "{}{}{}", self.section, self.variant.code(), SUFFIXE_CADRE),
+        }
+    }
+}
+
+impl FromStr for LayerId {
+    type Err = ErreurCalque;
+
+    fn from_str(s: &str) -> Result<LayerId, ErreurCalque> {
+        let invalide = || ErreurCalque::Invalide { calque: s.to_string() };
+        let (reste, arcane) = match s.strip_suffix(SUFFIXE_CADRE) {
+            Some(reste) => (reste, None),
+            None => {
+                let (reste, nombre) = s.split_at_checked(s.len().saturating_sub(2)).ok_or_else(invalide)?;
+                if nombre.len() != 2 || !nombre.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalide());
+                }
+                match nombre.parse::<usize>() {
+                    Ok(x) if (1..=22).contains(&x) => (reste, Some(x)),
+                    _ => return Err(ErreurCalque::Arcane { calque: s.to_string() }),
+                }
+            },
+        };
+        let (section, variante) = reste.split_at_checked(reste.len().saturating_sub(2)).ok_or_else(invalide)?;
+        if section.is_empty() {
+            return Err(invalide());
+        }
+        let variant = variante.parse::<VarianteCalque>().map_err(|_| invalide())?;
+        Ok(match arcane {
+            Some(x) => LayerId::valeur(section, variant, x),
+            None => LayerId::cadre_de(section, variant),
+        })
+    }
+}
+
+/*****************************************************
+ * Schéma de nommage des calques d'un template tiers *
+ * Motifs: {section} {variante} {arcane} {suffixe}   *
+ * arcane complété par des zéros sur `chiffres`      *
+ * positions: motifs propres à une section ("PPR")   *
+ * ou à une variante ("PPR.SA"), la variante         *
+ * l'emporte                                         *
+ * Par défaut: nommage ci-dessus, identique à        *
+ * LayerId::to_string                                *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Nommage {
+    pub valeur: String,
+    pub cadre: String,
+    pub chiffres: usize,
+    pub suffixe: String,
+    pub positions: BTreeMap<String, Motifs>,
+}
+
+// Motifs remplaçant ceux du schéma, absent = motif du schéma
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Motifs {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub valeur: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cadre: Option<String>,
+}
+
+impl Default for Nommage {
+    fn default() -> Nommage {
+        Nommage {
+            valeur: "{section}{variante}{arcane}".to_string(),
+            cadre: "{section}{variante}{suffixe}".to_string(),
+            chiffres: 2,
+            suffixe: SUFFIXE_CADRE.to_string(),
+            positions: BTreeMap::new(),
+        }
+    }
+}
+
+impl Nommage {
+    fn motif(&self, id: &LayerId) -> &str {
+        let cle = format!("{}.{}", id.section, id.variant.code());
+        let motifs = [self.positions.get(&cle), self.positions.get(&id.section)];
+        let motif = motifs.into_iter().flatten().find_map(|m| match id.kind {
+            Genre::Valeur => m.valeur.as_deref(),
+            Genre::Cadre => m.cadre.as_deref(),
+        });
+        motif.unwrap_or(match id.kind {
+            Genre::Valeur => &self.valeur,
+            Genre::Cadre => &self.cadre,
+        })
+    }
+
+    // Nom du calque dans le template
+    pub fn nommer(&self, id: &LayerId) -> String {
+        let arcane = id.arcane.map(|x| format!("{:0w$}", x, w = self.chiffres)).unwrap_or_default();
+        self.motif(id)
+            .replace("{section}", &id.section)
+            .replace("{variante}", id.variant.code())
+            .replace("{arcane}", &arcane)
+            .replace("{suffixe}", &self.suffixe)
+    }
+
+    /*************************************************
+     * Vérifie qu'aucun calque demandé ne partage    *
+     * son nom avec un autre                         *
+     *************************************************/
+    pub fn verifier(&self, calques: &[LayerId]) -> Result<(), ErreurCalque> {
+        let mut noms: BTreeMap<String, &LayerId> = BTreeMap::new();
+        for id in calques.iter() {
+            if let Some(autre) = noms.insert(self.nommer(id), id) {
+                return Err(ErreurCalque::Ambigu { premier: autre.to_string(), second: id.to_string(), calque: self.nommer(id) });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "toml")]
+pub fn nommage_toml(texte: &str) -> Result<Nommage, Erreur> {
+    toml::from_str(texte).map_err(|e| Erreur::new("erreur-nommage-invalide", &[("format", "TOML".to_string()), ("erreur", e.to_string())]))
+}
+
+#[cfg(feature = "ron")]
+pub fn nommage_ron(texte: &str) -> Result<Nommage, Erreur> {
+    ron::from_str(texte).map_err(|e| Erreur::new("erreur-nommage-invalide", &[("format", "RON".to_string()), ("erreur", e.to_string())]))
+}
+
+// Format d'après l'extension (.toml ou .ron), vérifié sur les calques de la table intégrée
+#[cfg(all(any(feature = "toml", feature = "ron"), not(target_arch = "wasm32")))]
+pub fn ouvrir_nommage(path: &str) -> Result<Nommage, Erreur> {
+    let texte = std::fs::read_to_string(path).map_err(|e| Erreur::new("erreur-fichier-lecture", &[("chemin", path.to_string())]).avec_cause(e))?;
+    let nommage = match path {
+        #[cfg(feature = "toml")]
+        p if p.ends_with(".toml") => nommage_toml(&texte)?,
+        #[cfg(feature = "ron")]
+        p if p.ends_with(".ron") => nommage_ron(&texte)?,
+        _ => return Err(Erreur::new("erreur-nommage-format", &[("chemin", path.to_string())])),
+    };
+    nommage
+        .verifier(&crate::ids_calques_attendus())
+        .map_err(|e| Erreur::new("erreur-nommage-verification", &[("chemin", path.to_string())]).avec_cause(e))?;
+    Ok(nommage)
+}
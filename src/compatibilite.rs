@@ -0,0 +1,85 @@
+#[cfg(feature = "image")]
+use std::error::Error;
+#[cfg(feature = "image")]
+use image::RgbaImage;
+use crate::tableau::{cycles, Arcane, Position, Tableau};
+#[cfg(feature = "image")]
+use crate::template::planche;
+#[cfg(feature = "image")]
+use crate::Template;
+use crate::reduction_theosophique;
+
+/*************************************************
+ * Tableau de couple (ou de groupe)              *
+ * Chaque variante = réduction de la somme des   *
+ * variantes de chacun, visible si elle l'est    *
+ * chez l'un d'eux                               *
+ * Le cycle commun part de la somme des          *
+ * pulsions, puis action et réaction comme pour  *
+ * un tableau individuel, sur le PPR commun      *
+ *************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compatibilite {
+    pub a: Tableau,
+    pub b: Tableau,
+    // Date et âge à 0: le tableau commun n'a pas de date de naissance
+    pub couple: Tableau,
+}
+
+pub fn compatibility(a: &Tableau, b: &Tableau) -> Compatibilite {
+    Compatibilite { a: *a, b: *b, couple: combiner(&[*a, *b]) }
+}
+
+/*************************************************
+ * Combinaison de N tableaux (couple, groupe)    *
+ *************************************************/
+pub fn combiner(tableaux: &[Tableau]) -> Tableau {
+    let somme = |f: &dyn Fn(&Tableau) -> Position| -> Position {
+        let s = |g: &dyn Fn(&Position) -> Arcane| Arcane::new(
+            reduction_theosophique(tableaux.iter().map(|t| g(&f(t)).valeur).sum(), false),
+            tableaux.iter().any(|t| g(&f(t)).visible),
+        );
+        Position {
+            pa: s(&|p| p.pa),
+            pb: s(&|p| p.pb),
+            sa: s(&|p| p.sa),
+            sb: s(&|p| p.sb),
+        }
+    };
+    let ppr = somme(&|t| t.ppr);
+    let nem = somme(&|t| t.nem);
+    let pex = somme(&|t| t.pex);
+    let pulsion = reduction_theosophique(tableaux.iter().map(|t| t.cycle_ppr.pulsion.pa.valeur).sum(), false);
+    let [cycle_ppr, cycle_nem, cycle_pex] = cycles(pulsion, &ppr, &nem, &pex);
+    Tableau {
+        jour: 0,
+        mois: 0,
+        annee: 0,
+        age: 0,
+        cai: somme(&|t| t.cai),
+        int: somme(&|t| t.int),
+        cae: somme(&|t| t.cae),
+        coi: somme(&|t| t.coi),
+        coe: somme(&|t| t.coe),
+        nem,
+        pex,
+        ppr,
+        cycle_ppr,
+        cycle_nem,
+        cycle_pex,
+    }
+}
+
+/*****************************************
+ * Rendu côte à côte: A | couple | B     *
+ *****************************************/
+#[cfg(feature = "image")]
+pub fn rendre_compatibilite(template: &Template, compatibilite: &Compatibilite) -> Result<RgbaImage, Box<dyn Error>> {
+    let images = [
+        template.image_tableau(&compatibilite.a)?,
+        template.image_tableau(&compatibilite.couple)?,
+        template.image_tableau(&compatibilite.b)?,
+    ];
+    Ok(planche(&images, images.len()))
+}
@@ -0,0 +1,211 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::langue::{message, Langue};
+
+/*****************************************************
+ * Erreurs de date et d'âge, traduites par           *
+ * message(langue)                                   *
+ * Display: français                                 *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurDate {
+    Format { texte: String },
+    Annee { annee: usize },
+    Mois { mois: usize },
+    Jour { jour: usize, mois: usize, annee: usize },
+    LectureAvantNaissance { naissance: Date, lecture: Date },
+    Age { age: usize },
+    PlageAges { de: usize, a: usize },
+    PlageAnnees { de: usize, a: usize },
+}
+
+impl ErreurDate {
+    pub fn message(&self, langue: Langue) -> String {
+        match self {
+            ErreurDate::Format { texte } => message(langue, "erreur-date", &[("texte", texte.clone())]),
+            ErreurDate::Annee { annee } => message(langue, "erreur-annee", &[("annee", annee.to_string())]),
+            ErreurDate::Mois { mois } => message(langue, "erreur-mois", &[("mois", mois.to_string())]),
+            ErreurDate::Jour { jour, mois, annee } => message(langue, "erreur-jour", &[
+                ("date", format!("{:02}.{:02}.{}", jour, mois, annee)),
+            ]),
+            ErreurDate::LectureAvantNaissance { naissance, lecture } => message(langue, "erreur-lecture-avant-naissance", &[
+                ("lecture", lecture.to_string()),
+                ("naissance", naissance.to_string()),
+            ]),
+            ErreurDate::Age { age } => message(langue, "erreur-age", &[("age", age.to_string()), ("max", AGE_MAX.to_string())]),
+            ErreurDate::PlageAges { de, a } => message(langue, "erreur-plage-ages", &[("de", de.to_string()), ("a", a.to_string())]),
+            ErreurDate::PlageAnnees { de, a } => message(langue, "erreur-plage-annees", &[("de", de.to_string()), ("a", a.to_string())]),
+        }
+    }
+}
+
+impl fmt::Display for ErreurDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Langue::default()))
+    }
+}
+
+impl Error for ErreurDate {}
+
+/*********************************
+ * Date du calendrier grégorien  *
+ * (naissance ou jour de lecture) *
+ *********************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Date {
+    pub annee: usize,
+    pub mois: usize,
+    pub jour: usize,
+}
+
+impl Date {
+    // Année 0 exclue: réservée au tableau combiné (sans date de naissance)
+    pub fn new(jour: usize, mois: usize, annee: usize) -> Result<Date, ErreurDate> {
+        if annee == 0 {
+            return Err(ErreurDate::Annee { annee });
+        }
+        if !(1..=12).contains(&mois) {
+            return Err(ErreurDate::Mois { mois });
+        }
+        if jour == 0 || jour > jours_dans_mois(mois, annee) {
+            return Err(ErreurDate::Jour { jour, mois, annee });
+        }
+        Ok(Date { annee, mois, jour })
+    }
+
+    // Âge révolu au jour de la lecture (None si la lecture précède la naissance)
+    pub fn age_au(&self, lecture: &Date) -> Option<usize> {
+        if lecture < self {
+            return None;
+        }
+        let mut age = lecture.annee - self.annee;
+        if (lecture.mois, lecture.jour) < (self.mois, self.jour) {
+            age -= 1;
+        }
+        Some(age)
+    }
+
+    // Idem, erreur si la lecture précède la naissance ou si l'âge dépasse AGE_MAX
+    pub fn age_le(&self, lecture: &Date) -> Result<usize, ErreurDate> {
+        let age = self
+            .age_au(lecture)
+            .ok_or(ErreurDate::LectureAvantNaissance { naissance: *self, lecture: *lecture })?;
+        verifier_age(age)
+    }
+
+    // Date du jour (UTC), d'après l'horloge système
+    pub fn aujourd_hui() -> Date {
+        #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+        let secondes = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        // SystemTime n'est pas disponible dans le navigateur
+        #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+        let secondes = (js_sys::Date::now() / 1000.0) as u64;
+        let mut date = Date { annee: 1970, mois: 1, jour: 1 };
+        date.avancer((secondes / 86_400) as usize);
+        date
+    }
+
+    fn avancer(&mut self, mut jours: usize) {
+        while jours > 0 {
+            let longueur_annee = if est_bissextile(self.annee) { 366 } else { 365 };
+            if self.mois == 1 && self.jour == 1 && jours >= longueur_annee {
+                self.annee += 1;
+                jours -= longueur_annee;
+            } else {
+                *self = self.lendemain();
+                jours -= 1;
+            }
+        }
+    }
+
+    pub fn lendemain(&self) -> Date {
+        if self.jour < jours_dans_mois(self.mois, self.annee) {
+            Date { jour: self.jour + 1, ..*self }
+        } else if self.mois < 12 {
+            Date { jour: 1, mois: self.mois + 1, ..*self }
+        } else {
+            Date { jour: 1, mois: 1, annee: self.annee + 1 }
+        }
+    }
+}
+
+/*********************************************
+ * Âge maximal accepté par la ligne de       *
+ * commande, le service HTTP et les liaisons *
+ *********************************************/
+pub const AGE_MAX: usize = 150;
+
+pub fn verifier_age(age: usize) -> Result<usize, ErreurDate> {
+    if age > AGE_MAX {
+        return Err(ErreurDate::Age { age });
+    }
+    Ok(age)
+}
+
+// Plage d'âges non vide, bornée par AGE_MAX
+pub fn plage_ages(de: usize, a: usize) -> Result<RangeInclusive<usize>, ErreurDate> {
+    if a < de {
+        return Err(ErreurDate::PlageAges { de, a });
+    }
+    verifier_age(a)?;
+    Ok(de..=a)
+}
+
+pub fn est_bissextile(annee: usize) -> bool {
+    (annee.is_multiple_of(4) && !annee.is_multiple_of(100)) || annee.is_multiple_of(400)
+}
+
+pub fn jours_dans_mois(mois: usize, annee: usize) -> usize {
+    match mois {
+        2 => if est_bissextile(annee) { 29 } else { 28 },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}.{:02}.{:04}", self.jour, self.mois, self.annee)
+    }
+}
+
+/***************************************
+ * Formats acceptés:                   *
+ * JJ.MM.AAAA, JJ/MM/AAAA, AAAA-MM-JJ  *
+ ***************************************/
+impl FromStr for Date {
+    type Err = ErreurDate;
+
+    fn from_str(s: &str) -> Result<Date, ErreurDate> {
+        let s = s.trim();
+        let invalide = || ErreurDate::Format { texte: s.to_string() };
+        let nombres = |parts: Vec<&str>| -> Result<Vec<usize>, ErreurDate> {
+            parts
+                .iter()
+                .map(|p| p.parse::<usize>().map_err(|_| invalide()))
+                .collect()
+        };
+        let (jour, mois, annee) = if s.contains('-') {
+            let n = nombres(s.split('-').collect())?;
+            if n.len() != 3 {
+                return Err(invalide());
+            }
+            (n[2], n[1], n[0])
+        } else {
+            let n = nombres(s.split(['.', '/']).collect())?;
+            if n.len() != 3 {
+                return Err(invalide());
+            }
+            (n[0], n[1], n[2])
+        };
+        Date::new(jour, mois, annee)
+    }
+}
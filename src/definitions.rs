@@ -0,0 +1,49 @@
+pub use cycles_numerologie_du_tarot_noyau::definitions::*;
+pub use cycles_numerologie_du_tarot_noyau::plan::Plan;
+#[cfg(any(feature = "toml", feature = "ron"))]
+use crate::langue::Erreur;
+
+/*****************************************************
+ * Table des positions chargée d'un fichier TOML ou  *
+ * RON, même forme que Definitions::par_defaut       *
+ * (voir exemples/positions.toml)                    *
+ * La table est compilée au chargement pour signaler *
+ * références inconnues et cycles                    *
+ *****************************************************/
+#[cfg(feature = "toml")]
+pub fn definitions_toml(texte: &str) -> Result<Definitions, Erreur> {
+    verifier(toml::from_str(texte).map_err(|e| Erreur::new("erreur-positions-invalides", &[("format", "TOML".to_string()), ("erreur", e.to_string())]))?)
+}
+
+#[cfg(feature = "ron")]
+pub fn definitions_ron(texte: &str) -> Result<Definitions, Erreur> {
+    verifier(ron::from_str(texte).map_err(|e| Erreur::new("erreur-positions-invalides", &[("format", "RON".to_string()), ("erreur", e.to_string())]))?)
+}
+
+// Format d'après l'extension (.toml ou .ron)
+#[cfg(all(any(feature = "toml", feature = "ron"), not(target_arch = "wasm32")))]
+pub fn ouvrir_definitions(path: &str) -> Result<Definitions, Erreur> {
+    let texte = std::fs::read_to_string(path).map_err(|e| Erreur::new("erreur-fichier-lecture", &[("chemin", path.to_string())]).avec_cause(e))?;
+    #[cfg(feature = "toml")]
+    if path.ends_with(".toml") {
+        return definitions_toml(&texte);
+    }
+    #[cfg(feature = "ron")]
+    if path.ends_with(".ron") {
+        return definitions_ron(&texte);
+    }
+    Err(Erreur::new("erreur-positions-format", &[("chemin", path.to_string())]))
+}
+
+#[cfg(any(feature = "toml", feature = "ron"))]
+fn verifier(definitions: Definitions) -> Result<Definitions, Erreur> {
+    for (i, d) in definitions.positions.iter().enumerate() {
+        if definitions.positions[..i].iter().any(|p| p.nom == d.nom) {
+            return Err(Erreur::new("erreur-position-double", &[("nom", d.nom.clone())]));
+        }
+    }
+    definitions
+        .compiler()
+        .map_err(|e| Erreur::new("erreur-positions-evaluation", &[]).avec_cause(e))?;
+    Ok(definitions)
+}
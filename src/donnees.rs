@@ -0,0 +1,127 @@
+use std::io::{self, Write};
+use crate::tableau::NOMS_POSITIONS;
+use crate::{calcul, Date, Tableau};
+
+const VARIANTES: [&str; 4] = ["PA", "PB", "SA", "SB"];
+const NB_VALEURS: usize = NOMS_POSITIONS.len() * VARIANTES.len();
+
+/*******************************************************
+ * Jeu de données: une ligne par date de naissance     *
+ * naissance,annee,mois,jour,age puis, pour chaque     *
+ * position et variante: POSITION_VARIANTE (arcane)    *
+ * et POSITION_VARIANTE_VISIBLE (0/1)                  *
+ * age: âge utilisé pour les positions du cycle        *
+ *******************************************************/
+pub fn colonnes() -> Vec<String> {
+    let mut colonnes: Vec<String> = ["naissance", "annee", "mois", "jour", "age"].iter().map(|c| c.to_string()).collect();
+    for position in NOMS_POSITIONS.iter() {
+        for variante in VARIANTES.iter() {
+            colonnes.push(format!("{}_{}", position, variante));
+            colonnes.push(format!("{}_{}_VISIBLE", position, variante));
+        }
+    }
+    colonnes
+}
+
+// (arcane, visible) dans l'ordre de colonnes()
+fn valeurs(tableau: &Tableau) -> [(usize, bool); NB_VALEURS] {
+    let positions = tableau.positions();
+    std::array::from_fn(|i| {
+        let arcane = positions[i / 4].1.variantes()[i % 4].1;
+        (arcane.valeur, arcane.visible)
+    })
+}
+
+// Date ISO, lue telle quelle par les tableurs et pandas
+fn iso(date: &Date) -> String {
+    format!("{:04}-{:02}-{:02}", date.annee, date.mois, date.jour)
+}
+
+// Retourne le nombre de lignes écrites (sans l'en-tête)
+pub fn exporter_csv<W: Write>(debut: &Date, fin: &Date, age: usize, sortie: W) -> io::Result<usize> {
+    let mut sortie = io::BufWriter::new(sortie);
+    writeln!(sortie, "{}", colonnes().join(","))?;
+    let mut lignes = 0;
+    let mut date = *debut;
+    while date <= *fin {
+        let tableau = calcul(date.jour, date.mois, date.annee, age);
+        write!(sortie, "{},{},{},{},{}", iso(&date), date.annee, date.mois, date.jour, age)?;
+        for (valeur, visible) in valeurs(&tableau) {
+            write!(sortie, ",{},{}", valeur, visible as u8)?;
+        }
+        writeln!(sortie)?;
+        lignes += 1;
+        date = date.lendemain();
+    }
+    sortie.flush()?;
+    Ok(lignes)
+}
+
+/*********************************************
+ * Parquet: mêmes colonnes que le CSV,       *
+ * valeurs en INT32, visibilité en BOOLEAN,  *
+ * un groupe de lignes par tranche de dates  *
+ *********************************************/
+#[cfg(feature = "parquet")]
+pub fn exporter_parquet<W: Write + Send>(debut: &Date, fin: &Date, age: usize, sortie: W) -> Result<usize, Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use parquet::data_type::{BoolType, ByteArray, ByteArrayType, Int32Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+
+    const LIGNES_PAR_GROUPE: usize = 65536;
+
+    let mut schema = String::from("message tableau {\n  required binary naissance (UTF8);\n");
+    for colonne in colonnes().iter().skip(1) {
+        let type_colonne = if colonne.ends_with("_VISIBLE") { "boolean" } else { "int32" };
+        schema.push_str(&format!("  required {} {};\n", type_colonne, colonne));
+    }
+    schema.push('}');
+    let schema = Arc::new(parse_message_type(&schema)?);
+    let mut writer = SerializedFileWriter::new(sortie, schema, Arc::new(WriterProperties::builder().build()))?;
+
+    let mut lignes = 0;
+    let mut date = *debut;
+    while date <= *fin {
+        let mut naissances: Vec<ByteArray> = Vec::with_capacity(LIGNES_PAR_GROUPE);
+        let mut entiers: [Vec<i32>; 4] = Default::default();
+        let mut arcanes: Vec<Vec<i32>> = (0..NB_VALEURS).map(|_| Vec::with_capacity(LIGNES_PAR_GROUPE)).collect();
+        let mut visibles: Vec<Vec<bool>> = (0..NB_VALEURS).map(|_| Vec::with_capacity(LIGNES_PAR_GROUPE)).collect();
+        while date <= *fin && naissances.len() < LIGNES_PAR_GROUPE {
+            let tableau = calcul(date.jour, date.mois, date.annee, age);
+            naissances.push(ByteArray::from(iso(&date).as_str()));
+            for (colonne, x) in entiers.iter_mut().zip([date.annee, date.mois, date.jour, age]) {
+                colonne.push(x as i32);
+            }
+            for (i, (valeur, visible)) in valeurs(&tableau).into_iter().enumerate() {
+                arcanes[i].push(valeur as i32);
+                visibles[i].push(visible);
+            }
+            date = date.lendemain();
+        }
+        lignes += naissances.len();
+
+        let mut groupe = writer.next_row_group()?;
+        let mut indice = 0;
+        while let Some(mut colonne) = groupe.next_column()? {
+            match indice {
+                0 => { colonne.typed::<ByteArrayType>().write_batch(&naissances, None, None)?; },
+                1..=4 => { colonne.typed::<Int32Type>().write_batch(&entiers[indice - 1], None, None)?; },
+                _ => {
+                    let i = (indice - 5) / 2;
+                    if (indice - 5) % 2 == 0 {
+                        colonne.typed::<Int32Type>().write_batch(&arcanes[i], None, None)?;
+                    } else {
+                        colonne.typed::<BoolType>().write_batch(&visibles[i], None, None)?;
+                    }
+                },
+            }
+            colonne.close()?;
+            indice += 1;
+        }
+        groupe.close()?;
+    }
+    writer.close()?;
+    Ok(lignes)
+}
@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::io::Cursor;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use image::{DynamicImage, RgbImage, RgbaImage};
+use crate::template::{encoder_png, ErreurTemplate};
+
+/*****************************************
+ * SVG matriciel: enveloppe du PNG,      *
+ * embarqué en data URI (aucun tracé     *
+ * vectoriel, même résolution que le     *
+ * PNG)                                  *
+ *****************************************/
+pub fn encoder_svg_matriciel(image: &RgbaImage) -> Result<Vec<u8>, Box<dyn Error>> {
+    let png = encoder_png(image)?;
+    let (w, h) = image.dimensions();
+    let svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\
+<image width=\"{w}\" height=\"{h}\" xlink:href=\"{}\"/></svg>",
+        data_uri_png(&png)
+    );
+    Ok(svg.into_bytes())
+}
+
+pub fn data_uri_png(png: &[u8]) -> String {
+    format!("data:image/png;base64,{}", STANDARD.encode(png))
+}
+
+/**************************************************
+ * PDF d'une page à la taille de l'image (72 dpi) *
+ * L'image est aplatie sur fond blanc en JPEG     *
+ **************************************************/
+pub fn encoder_pdf(image: &RgbaImage) -> Result<Vec<u8>, Box<dyn Error>> {
+    encoder_pdf_avec_texte(image, "")
+}
+
+const LIGNES_PAR_PAGE: usize = 60;
+
+/****************************************************
+ * Idem, suivi de pages A4 de texte (Courier,       *
+ * WinAnsi): une ligne du texte par ligne de page   *
+ ****************************************************/
+pub fn encoder_pdf_avec_texte(image: &RgbaImage, texte: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (w, h) = image.dimensions();
+    let mut rgb = RgbImage::new(w, h);
+    for (x, y, px) in image.enumerate_pixels() {
+        let a = px[3] as f32 / 255.0;
+        let fond = |c: u8| (c as f32 * a + 255.0 * (1.0 - a)).round() as u8;
+        rgb.put_pixel(x, y, image::Rgb([fond(px[0]), fond(px[1]), fond(px[2])]));
+    }
+    let mut jpeg = Vec::new();
+    DynamicImage::ImageRgb8(rgb)
+        .write_to(&mut Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+        .map_err(|e| ErreurTemplate::Encodage { format: "JPEG", erreur: e.to_string() })?;
+
+    let lignes: Vec<&str> = texte.lines().collect();
+    let pages_texte: Vec<&[&str]> = lignes.chunks(LIGNES_PAR_PAGE).collect();
+    // Objets 1 à 6: catalogue, pages, page image, image, contenu image, police
+    // puis une page et son contenu par page de texte
+    let kids: Vec<String> = std::iter::once(3)
+        .chain((0..pages_texte.len()).map(|i| 7 + 2 * i))
+        .map(|n| format!("{} 0 R", n))
+        .collect();
+    let contenu = format!("q {} 0 0 {} 0 0 cm /Im0 Do Q", w, h);
+    let mut objets: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), kids.len()).into_bytes(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /XObject << /Im0 4 0 R >> >> /Contents 5 0 R >>",
+            w, h
+        ).into_bytes(),
+        [
+            format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+                w, h, jpeg.len()
+            ).into_bytes(),
+            jpeg,
+            b"\nendstream".to_vec(),
+        ].concat(),
+        format!("<< /Length {} >>\nstream\n{}\nendstream", contenu.len(), contenu).into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    for (i, page) in pages_texte.iter().enumerate() {
+        let mut contenu = b"BT /F1 10 Tf 13 TL 50 800 Td\n".to_vec();
+        for ligne in page.iter() {
+            contenu.extend(chaine_pdf(ligne));
+            contenu.extend(b" '\n");
+        }
+        contenu.extend(b"ET");
+        objets.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] /Resources << /Font << /F1 6 0 R >> >> /Contents {} 0 R >>",
+            8 + 2 * i
+        ).into_bytes());
+        objets.push([
+            format!("<< /Length {} >>\nstream\n", contenu.len()).into_bytes(),
+            contenu,
+            b"\nendstream".to_vec(),
+        ].concat());
+    }
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n".to_vec();
+    let mut positions = vec![];
+    for (i, objet) in objets.iter().enumerate() {
+        positions.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+        pdf.extend(objet);
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objets.len() + 1).into_bytes());
+    for position in positions {
+        pdf.extend(format!("{:010} 00000 n \n", position).into_bytes());
+    }
+    pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objets.len() + 1, xref).into_bytes());
+    Ok(pdf)
+}
+
+// Chaîne littérale PDF en WinAnsi (Latin-1), caractères hors Latin-1 remplacés par '?'
+fn chaine_pdf(texte: &str) -> Vec<u8> {
+    let mut chaine = vec![b'('];
+    for c in texte.chars() {
+        match c {
+            '(' | ')' | '\\' => chaine.extend([b'\\', c as u8]),
+            '\u{2019}' => chaine.push(0x92),
+            c if (c as u32) < 0x100 => chaine.push(c as u32 as u8),
+            _ => chaine.push(b'?'),
+        }
+    }
+    chaine.push(b')');
+    chaine
+}
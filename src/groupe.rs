@@ -0,0 +1,144 @@
+#[cfg(feature = "image")]
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "image")]
+use image::RgbaImage;
+use crate::compatibilite::combiner;
+use crate::langue::{message, Langue, Localise};
+use crate::tableau::{Position, Tableau};
+#[cfg(feature = "image")]
+use crate::template::planche;
+#[cfg(feature = "image")]
+use crate::Template;
+
+// Positions de base comparées entre les membres
+const POSITIONS_BASE: [&str; 8] = ["CAI", "INT", "CAE", "COI", "COE", "NEM", "PEX", "PPR"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Membre {
+    pub nom: String,
+    pub tableau: Tableau,
+}
+
+/************************************************
+ * Analyse d'une position sur tout le groupe    *
+ * partages: arcanes visibles chez au moins 2   *
+ *           membres                            *
+ * manquants: arcanes (1 à 22) visibles chez    *
+ *            aucun membre                      *
+ ************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnalysePosition {
+    pub position: String,
+    pub par_membre: Vec<Vec<usize>>,
+    pub partages: Vec<usize>,
+    pub manquants: Vec<usize>,
+    pub groupe: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constellation {
+    pub membres: Vec<Membre>,
+    pub positions: Vec<AnalysePosition>,
+    // Arcanes absents de toutes les positions de base de tous les membres
+    pub manquants: Vec<usize>,
+    // Tableau combiné (mêmes règles que le tableau de couple)
+    pub groupe: Tableau,
+}
+
+fn visibles(p: &Position) -> Vec<usize> {
+    let mut v: Vec<usize> = p.variantes().iter().filter_map(|(_, a)| a.visible()).collect();
+    v.sort();
+    v.dedup();
+    v
+}
+
+pub fn constellation(membres: &[Membre]) -> Constellation {
+    let tableaux: Vec<Tableau> = membres.iter().map(|m| m.tableau).collect();
+    let groupe = combiner(&tableaux);
+    let mut positions = vec![];
+    let mut presents_groupe = [false; 23];
+    for (i, nom) in POSITIONS_BASE.iter().enumerate() {
+        let par_membre: Vec<Vec<usize>> = tableaux.iter().map(|t| visibles(&t.positions()[i].1)).collect();
+        let mut compte = [0usize; 23];
+        for arcanes in par_membre.iter() {
+            for x in arcanes.iter().filter(|x| **x <= 22) {
+                compte[*x] += 1;
+                presents_groupe[*x] = true;
+            }
+        }
+        positions.push(AnalysePosition {
+            position: nom.to_string(),
+            par_membre,
+            partages: (1..=22).filter(|x| compte[*x] >= 2).collect(),
+            manquants: (1..=22).filter(|x| compte[*x] == 0).collect(),
+            groupe: groupe.positions()[i].1,
+        });
+    }
+    Constellation {
+        membres: membres.to_vec(),
+        positions,
+        manquants: (1..=22).filter(|x| !presents_groupe[*x]).collect(),
+        groupe,
+    }
+}
+
+/***********************************************
+ * Planche: un tableau par membre puis le      *
+ * tableau du groupe                           *
+ ***********************************************/
+#[cfg(feature = "image")]
+pub fn rendre_constellation(template: &Template, constellation: &Constellation, colonnes: usize) -> Result<RgbaImage, Box<dyn Error>> {
+    let mut images = vec![];
+    for membre in constellation.membres.iter() {
+        images.push(template.image_tableau(&membre.tableau)?);
+    }
+    images.push(template.image_tableau(&constellation.groupe)?);
+    Ok(planche(&images, colonnes))
+}
+
+fn liste(v: &[usize]) -> String {
+    if v.is_empty() {
+        return "-".to_string();
+    }
+    v.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/*******************************************
+ * Table texte: une ligne par position,    *
+ * puis le cycle en cours de chaque membre *
+ *******************************************/
+impl Localise for Constellation {
+    fn fmt_langue(&self, f: &mut fmt::Formatter<'_>, langue: Langue) -> fmt::Result {
+        let groupe = message(langue, "constellation-groupe", &[]);
+        write!(f, "{:<6}", "")?;
+        for m in self.membres.iter() {
+            write!(f, " {:<12}", m.nom)?;
+        }
+        writeln!(f, " {:<12} {:<16} {}", groupe, message(langue, "constellation-partages", &[]), message(langue, "constellation-manquants", &[]))?;
+        for p in self.positions.iter() {
+            write!(f, "{:<6}", p.position)?;
+            for arcanes in p.par_membre.iter() {
+                write!(f, " {:<12}", liste(arcanes))?;
+            }
+            writeln!(f, " {:<12} {:<16} {}", liste(&visibles(&p.groupe)), liste(&p.partages), liste(&p.manquants))?;
+        }
+        writeln!(f, "{}", message(langue, "constellation-absents", &[("liste", liste(&self.manquants))]))?;
+        writeln!(f, "{}", message(langue, "constellation-cycle", &[]))?;
+        for m in self.membres.iter() {
+            let c = &m.tableau.cycle_ppr;
+            writeln!(f, "  {:<12} {} / {} / {}", m.nom, liste(&visibles(&c.pulsion)), liste(&visibles(&c.action)), liste(&visibles(&c.reaction)))?;
+        }
+        let c = &self.groupe.cycle_ppr;
+        writeln!(f, "  {:<12} {} / {} / {}", groupe, liste(&visibles(&c.pulsion)), liste(&visibles(&c.action)), liste(&visibles(&c.reaction)))
+    }
+}
+
+impl fmt::Display for Constellation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_langue(f, Langue::default())
+    }
+}
@@ -0,0 +1,136 @@
+use std::ops::RangeInclusive;
+use crate::langue::{message, nom_arcane_langue, nom_etape, Langue};
+use crate::personnel::{cycles_personnels, CyclesSections};
+use crate::tableau::Position;
+use crate::Date;
+
+/***************************************************
+ * Export iCalendar (RFC 5545) des changements de  *
+ * cycle: un événement par anniversaire et, en     *
+ * option, un par changement de mois personnel     *
+ * (le 1er de chaque mois)                         *
+ ***************************************************/
+#[derive(Debug, Clone)]
+pub struct OptionsIcal {
+    pub annees: RangeInclusive<usize>,
+    pub mois_personnels: bool,
+    // Ajouté au titre des événements, ex: le prénom du client
+    pub nom: Option<String>,
+    // Titres et descriptions des événements
+    pub langue: Langue,
+}
+
+// Variantes visibles avec leur nom: "8 (La Justice) / 17 (L'Étoile)"
+fn arcanes(p: &Position, langue: Langue) -> String {
+    p.variantes()
+        .iter()
+        .filter_map(|(_, a)| a.visible())
+        .map(|x| format!("{} ({})", x, nom_arcane_langue(x, langue).unwrap_or_else(|| "?".to_string())))
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+fn description(s: &CyclesSections, langue: Langue) -> String {
+    let [pulsion, action, reaction] = ["pulsion", "action", "reaction"].map(|e| nom_etape(e, langue));
+    let mut lignes = vec![format!("{}: {}", pulsion, arcanes(&s.ppr.pulsion, langue))];
+    for (nom, c) in [("PPR", &s.ppr), ("NEM", &s.nem), ("PEX", &s.pex)] {
+        lignes.push(format!("{} {}: {}", action, nom, arcanes(&c.action, langue)));
+        lignes.push(format!("{} {}: {}", reaction, nom, arcanes(&c.reaction, langue)));
+    }
+    lignes.join("\n")
+}
+
+// Échappement des TEXT iCalendar
+fn echapper(texte: &str) -> String {
+    texte
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Lignes de 75 octets maximum, suite précédée d'un espace
+fn plier(ligne: &str) -> String {
+    let mut resultat = String::new();
+    let mut longueur = 0;
+    for c in ligne.chars() {
+        if longueur + c.len_utf8() > 75 {
+            resultat.push_str("\r\n ");
+            longueur = 1;
+        }
+        resultat.push(c);
+        longueur += c.len_utf8();
+    }
+    resultat.push_str("\r\n");
+    resultat
+}
+
+fn date_ical(d: &Date) -> String {
+    format!("{:04}{:02}{:02}", d.annee, d.mois, d.jour)
+}
+
+fn evenement(naissance: &Date, date: &Date, titre: &str, description: &str, horodatage: &str) -> String {
+    [
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:cycle-{}-{}@cycles_numerologie_du_tarot", date_ical(naissance), date_ical(date)),
+        format!("DTSTAMP:{}", horodatage),
+        format!("DTSTART;VALUE=DATE:{}", date_ical(date)),
+        format!("DTEND;VALUE=DATE:{}", date_ical(&date.lendemain())),
+        format!("SUMMARY:{}", echapper(titre)),
+        format!("DESCRIPTION:{}", echapper(description)),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+    ]
+    .iter()
+    .map(|l| plier(l))
+    .collect()
+}
+
+// Anniversaire d'une année donnée (le 29 février tombe le 1er mars les années non bissextiles)
+fn anniversaire(naissance: &Date, annee: usize) -> Date {
+    Date::new(naissance.jour, naissance.mois, annee).unwrap_or(Date { annee, mois: 3, jour: 1 })
+}
+
+pub fn ical(naissance: &Date, options: &OptionsIcal) -> String {
+    let horodatage = format!("{}T000000Z", date_ical(&Date::aujourd_hui()));
+    let suffixe = options.nom.as_ref().map(|n| format!(" - {}", n)).unwrap_or_default();
+    let langue = options.langue;
+    let mut ics = String::new();
+    for ligne in [
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//cycles_numerologie_du_tarot//Cycles//{}", langue.code().to_uppercase()),
+        "CALSCALE:GREGORIAN".to_string(),
+    ] {
+        ics.push_str(&plier(&ligne));
+    }
+    for annee in options.annees.clone() {
+        let date = anniversaire(naissance, annee);
+        if let Some(c) = cycles_personnels(naissance, &date) {
+            let arguments = [("arcane", c.annee.ppr.pulsion.pa.valeur.to_string()), ("age", c.age.to_string())];
+            let titre = format!("{}{}", message(langue, "ical-cycle-annuel", &arguments), suffixe);
+            let texte = format!(
+                "{}\n\n{}:\n{}",
+                description(&c.annee, langue),
+                message(langue, "ical-mois-personnel-titre", &[]),
+                description(&c.mois, langue)
+            );
+            ics.push_str(&evenement(naissance, &date, &titre, &texte, &horodatage));
+        }
+        if options.mois_personnels {
+            for mois in 1..=12 {
+                let date = Date { annee, mois, jour: 1 };
+                if date == anniversaire(naissance, annee) {
+                    continue;
+                }
+                if let Some(c) = cycles_personnels(naissance, &date) {
+                    let arguments = [("arcane", c.mois.ppr.pulsion.pa.valeur.to_string())];
+                    let titre = format!("{}{}", message(langue, "ical-mois-personnel", &arguments), suffixe);
+                    ics.push_str(&evenement(naissance, &date, &titre, &description(&c.mois, langue), &horodatage));
+                }
+            }
+        }
+    }
+    ics.push_str(&plier("END:VCALENDAR"));
+    ics
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fmt;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use crate::langue::Erreur;
+use crate::langue::{nom_arcane_langue, Langue};
+use crate::Tableau;
+
+/*****************************************************
+ * Source des textes d'interprétation               *
+ * position: nom de NOMS_POSITIONS (PPR, NEM, ...,  *
+ * ACTION_PPR, ...), variante: PA, PB, SA, SB       *
+ *****************************************************/
+pub trait SourceTextes {
+    fn texte(&self, position: &str, variante: &str, arcane: usize) -> Option<String>;
+    fn description(&self, position: &str) -> Option<String>;
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TextesPosition {
+    pub description: Option<String>,
+    // Arcane ("1" à "22") -> texte
+    pub arcanes: BTreeMap<String, String>,
+    // Variante -> arcane -> texte, prioritaire sur arcanes
+    pub variantes: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl TextesPosition {
+    fn texte(&self, variante: &str, arcane: usize) -> Option<String> {
+        let cle = arcane.to_string();
+        self.variantes
+            .get(variante)
+            .and_then(|v| v.get(&cle))
+            .or_else(|| self.arcanes.get(&cle))
+            .cloned()
+    }
+}
+
+/*******************************************************
+ * Textes chargés d'un fichier TOML ou YAML:           *
+ * [arcanes]            sens général de chaque arcane  *
+ * [positions.PPR]      description, arcanes,          *
+ *                      variantes.SA, ...              *
+ * [cycles.action]      pulsion, action ou reaction,   *
+ *                      pour les positions du cycle    *
+ * Recherche: position, puis étape du cycle, puis      *
+ * sens général de l'arcane                            *
+ *******************************************************/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Interpretations {
+    pub arcanes: BTreeMap<String, String>,
+    pub positions: BTreeMap<String, TextesPosition>,
+    pub cycles: BTreeMap<String, TextesPosition>,
+}
+
+// ACTION_PPR -> action
+fn etape(position: &str) -> Option<String> {
+    position.split_once('_').map(|(e, _)| e.to_lowercase())
+}
+
+impl Interpretations {
+    #[cfg(feature = "toml")]
+    pub fn depuis_toml(texte: &str) -> Result<Interpretations, Erreur> {
+        toml::from_str(texte).map_err(|e| Erreur::new("erreur-textes-invalides", &[("format", "TOML".to_string()), ("erreur", e.to_string())]))
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn depuis_yaml(texte: &str) -> Result<Interpretations, Erreur> {
+        serde_yaml::from_str(texte).map_err(|e| Erreur::new("erreur-textes-invalides", &[("format", "YAML".to_string()), ("erreur", e.to_string())]))
+    }
+
+    // Format d'après l'extension (.toml, .yaml ou .yml)
+    #[cfg(all(any(feature = "toml", feature = "yaml"), not(target_arch = "wasm32")))]
+    pub fn ouvrir(path: &str) -> Result<Interpretations, Erreur> {
+        let texte = std::fs::read_to_string(path).map_err(|e| Erreur::new("erreur-fichier-lecture", &[("chemin", path.to_string())]).avec_cause(e))?;
+        #[cfg(feature = "yaml")]
+        if path.ends_with(".yaml") || path.ends_with(".yml") {
+            return Interpretations::depuis_yaml(&texte);
+        }
+        #[cfg(feature = "toml")]
+        if path.ends_with(".toml") {
+            return Interpretations::depuis_toml(&texte);
+        }
+        Err(Erreur::new("erreur-textes-format", &[("chemin", path.to_string())]))
+    }
+}
+
+impl SourceTextes for Interpretations {
+    fn texte(&self, position: &str, variante: &str, arcane: usize) -> Option<String> {
+        self.positions
+            .get(position)
+            .and_then(|p| p.texte(variante, arcane))
+            .or_else(|| etape(position).and_then(|e| self.cycles.get(&e)).and_then(|c| c.texte(variante, arcane)))
+            .or_else(|| self.arcanes.get(&arcane.to_string()).cloned())
+    }
+
+    fn description(&self, position: &str) -> Option<String> {
+        self.positions
+            .get(position)
+            .and_then(|p| p.description.clone())
+            .or_else(|| etape(position).and_then(|e| self.cycles.get(&e)).and_then(|c| c.description.clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Paragraphe {
+    pub position: String,
+    pub description: Option<String>,
+    // "PA 19 (Le Soleil): texte", une ligne par arcane visible
+    pub lignes: Vec<String>,
+}
+
+/*****************************************************
+ * Lecture: un paragraphe par position du tableau,   *
+ * positions de base puis cycle en cours             *
+ * Un arcane répété dans la position n'est lu        *
+ * qu'une fois; sans texte, seul son nom figure      *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lecture {
+    pub paragraphes: Vec<Paragraphe>,
+}
+
+pub fn lecture(tableau: &Tableau, textes: &dyn SourceTextes) -> Lecture {
+    lecture_langue(tableau, textes, Langue::default())
+}
+
+// Noms des arcanes dans la langue demandée, les textes restent ceux de la source
+pub fn lecture_langue(tableau: &Tableau, textes: &dyn SourceTextes, langue: Langue) -> Lecture {
+    let paragraphes = tableau
+        .positions()
+        .iter()
+        .map(|(position, p)| {
+            let mut vus = vec![];
+            let mut lignes = vec![];
+            for (variante, arcane) in p.variantes().iter() {
+                if let Some(x) = arcane.visible().filter(|x| !vus.contains(x)) {
+                    vus.push(x);
+                    let nom = nom_arcane_langue(x, langue).unwrap_or_default();
+                    lignes.push(match textes.texte(position, variante, x) {
+                        Some(t) => format!("{} {} ({}): {}", variante, x, nom, t.trim()),
+                        None => format!("{} {} ({})", variante, x, nom),
+                    });
+                }
+            }
+            Paragraphe { position: position.to_string(), description: textes.description(position), lignes }
+        })
+        .collect();
+    Lecture { paragraphes }
+}
+
+impl fmt::Display for Lecture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for p in self.paragraphes.iter() {
+            match p.description.as_deref() {
+                Some(d) => writeln!(f, "{}: {}", p.position, d.trim())?,
+                None => writeln!(f, "{}", p.position)?,
+            }
+            for l in p.lignes.iter() {
+                writeln!(f, "  {}", l)?;
+            }
+        }
+        Ok(())
+    }
+}
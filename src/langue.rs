@@ -0,0 +1,296 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+#[cfg(feature = "fluent")]
+use fluent_bundle::concurrent::FluentBundle;
+#[cfg(feature = "fluent")]
+use fluent_bundle::{FluentArgs, FluentResource};
+#[cfg(feature = "fluent")]
+use unic_langid::LanguageIdentifier;
+#[cfg(feature = "image")]
+use crate::template::ErreurTemplate;
+use crate::calque::ErreurCalque;
+use crate::date::ErreurDate;
+use crate::definitions::ErreurDefinition;
+use crate::Tableau;
+
+/*****************************************************
+ * Langue des textes destinés à l'utilisateur:       *
+ * erreurs, noms des arcanes et des positions,       *
+ * rapports. Catalogues Fluent dans src/locales      *
+ * (fluent-bundle avec la feature "fluent")          *
+ *****************************************************/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Langue {
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "fr"))]
+    Francais,
+    #[cfg_attr(feature = "serde", serde(rename = "en"))]
+    Anglais,
+}
+
+impl Langue {
+    pub const TOUTES: [Langue; 2] = [Langue::Francais, Langue::Anglais];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Langue::Francais => "fr",
+            Langue::Anglais => "en",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Langue::Francais => include_str!("locales/fr.ftl"),
+            Langue::Anglais => include_str!("locales/en.ftl"),
+        }
+    }
+}
+
+impl fmt::Display for Langue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+// "fr", "en", ou une étiquette régionale: "fr-CH", "en_GB"
+impl FromStr for Langue {
+    type Err = Erreur;
+
+    fn from_str(s: &str) -> Result<Langue, Erreur> {
+        let code = s.trim().split(['-', '_']).next().unwrap_or("").to_lowercase();
+        Langue::TOUTES
+            .into_iter()
+            .find(|l| l.code() == code)
+            .ok_or_else(|| Erreur::new("erreur-langue", &[("langue", s.trim().to_string())]))
+    }
+}
+
+/*****************************************************
+ * Affichage dans une langue donnée:                 *
+ * println!("{}", tableau.dans(Langue::Anglais))     *
+ * Display des types localisés = français            *
+ *****************************************************/
+pub trait Localise {
+    fn fmt_langue(&self, f: &mut fmt::Formatter<'_>, langue: Langue) -> fmt::Result;
+
+    fn dans(&self, langue: Langue) -> DansLangue<'_, Self> where Self: Sized {
+        DansLangue { valeur: self, langue }
+    }
+}
+
+pub struct DansLangue<'a, T> {
+    valeur: &'a T,
+    langue: Langue,
+}
+
+impl<T: Localise> fmt::Display for DansLangue<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.valeur.fmt_langue(f, self.langue)
+    }
+}
+
+#[cfg(feature = "fluent")]
+type Catalogue = FluentBundle<FluentResource>;
+
+#[cfg(feature = "fluent")]
+fn catalogue(langue: Langue) -> &'static Catalogue {
+    static CATALOGUES: [OnceLock<Catalogue>; 2] = [OnceLock::new(), OnceLock::new()];
+    let indice = Langue::TOUTES.iter().position(|l| *l == langue).unwrap_or(0);
+    CATALOGUES[indice].get_or_init(|| {
+        let id: LanguageIdentifier = langue.code().parse().expect("code de langue valide");
+        let mut catalogue = FluentBundle::new_concurrent(vec![id]);
+        // Pas de caractères d'isolation bidirectionnelle autour des arguments
+        catalogue.set_use_isolating(false);
+        let ressource = FluentResource::try_new(langue.source().to_string()).expect("catalogue Fluent valide");
+        catalogue.add_resource(ressource).expect("messages Fluent uniques");
+        catalogue
+    })
+}
+
+/*****************************************************
+ * Message du catalogue, arguments en texte          *
+ * Un message absent de la langue est cherché en     *
+ * français, puis l'identifiant est renvoyé tel quel *
+ *****************************************************/
+#[cfg(feature = "fluent")]
+pub fn message(langue: Langue, id: &str, arguments: &[(&str, String)]) -> String {
+    let mut args = FluentArgs::new();
+    for (nom, valeur) in arguments {
+        args.set(*nom, valeur.clone());
+    }
+    for l in [langue, Langue::Francais] {
+        let catalogue = catalogue(l);
+        if let Some(pattern) = catalogue.get_message(id).and_then(|m| m.value()) {
+            let mut erreurs = vec![];
+            return catalogue.format_pattern(pattern, Some(&args), &mut erreurs).into_owned();
+        }
+    }
+    id.to_string()
+}
+
+/*****************************************************
+ * Sans fluent-bundle: les catalogues n'utilisent    *
+ * que des messages d'une ligne, des variables       *
+ * { $nom } et des littéraux {"{"}: même rendu       *
+ *****************************************************/
+#[cfg(not(feature = "fluent"))]
+type Catalogue = std::collections::HashMap<&'static str, &'static str>;
+
+#[cfg(not(feature = "fluent"))]
+fn catalogue(langue: Langue) -> &'static Catalogue {
+    static CATALOGUES: [OnceLock<Catalogue>; 2] = [OnceLock::new(), OnceLock::new()];
+    let indice = Langue::TOUTES.iter().position(|l| *l == langue).unwrap_or(0);
+    CATALOGUES[indice].get_or_init(|| {
+        langue
+            .source()
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .filter_map(|l| l.split_once(" = "))
+            .map(|(id, motif)| (id.trim(), motif.trim()))
+            .collect()
+    })
+}
+
+#[cfg(not(feature = "fluent"))]
+pub fn message(langue: Langue, id: &str, arguments: &[(&str, String)]) -> String {
+    for l in [langue, Langue::Francais] {
+        if let Some(motif) = catalogue(l).get(id) {
+            let mut texte = String::new();
+            let mut reste = *motif;
+            while let Some((avant, apres)) = reste.split_once('{') {
+                texte.push_str(avant);
+                let apres = apres.trim_start();
+                let (place, suite) = match apres.strip_prefix('"') {
+                    Some(litteral) => litteral.split_once('"').map(|(l, s)| (l.to_string(), s.trim_start().strip_prefix('}').unwrap_or(s))),
+                    None => apres.split_once('}').map(|(v, s)| {
+                        let nom = v.trim().trim_start_matches('$');
+                        // Variable absente: rendue comme le fait Fluent
+                        (arguments.iter().find(|(n, _)| *n == nom).map_or_else(|| format!("{{${}}}", nom), |(_, v)| v.clone()), s)
+                    }),
+                }
+                .unwrap_or_default();
+                texte.push_str(&place);
+                reste = suite;
+            }
+            texte.push_str(reste);
+            return texte;
+        }
+    }
+    id.to_string()
+}
+
+pub fn nom_arcane_langue(n: usize, langue: Langue) -> Option<String> {
+    if (1..=22).contains(&n) {
+        Some(message(langue, &format!("arcane-{}", n), &[]))
+    } else {
+        None
+    }
+}
+
+// Sigle de NOMS_POSITIONS -> nom complet, ex: PPR, ACTION_NEM
+pub fn nom_position(position: &str, langue: Langue) -> String {
+    match position.split_once('_') {
+        Some((etape, base)) => format!("{} {}", nom_etape(etape, langue), base),
+        None => message(langue, &format!("position-{}", position), &[]),
+    }
+}
+
+// PULSION, ACTION ou REACTION
+pub fn nom_etape(etape: &str, langue: Langue) -> String {
+    message(langue, &format!("etape-{}", etape.to_lowercase()), &[])
+}
+
+/*****************************************************
+ * Erreur du catalogue: identifiant du message et    *
+ * arguments, traduite par message_erreur            *
+ * cause: erreur traduite elle aussi, passée en      *
+ * argument $erreur                                  *
+ * Display: français                                 *
+ *****************************************************/
+#[derive(Debug)]
+pub struct Erreur {
+    pub id: &'static str,
+    pub arguments: Vec<(&'static str, String)>,
+    pub cause: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl Erreur {
+    pub fn new(id: &'static str, arguments: &[(&'static str, String)]) -> Erreur {
+        Erreur { id, arguments: arguments.to_vec(), cause: None }
+    }
+
+    pub fn avec_cause(mut self, cause: impl Into<Box<dyn Error + Send + Sync>>) -> Erreur {
+        self.cause = Some(cause.into());
+        self
+    }
+
+    pub fn message(&self, langue: Langue) -> String {
+        let mut arguments = self.arguments.clone();
+        if let Some(cause) = self.cause.as_deref() {
+            arguments.push(("erreur", message_erreur(cause, langue)));
+        }
+        message(langue, self.id, &arguments)
+    }
+}
+
+impl fmt::Display for Erreur {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Langue::default()))
+    }
+}
+
+impl Error for Erreur {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause.as_deref().map(|c| c as &(dyn Error + 'static))
+    }
+}
+
+// Erreur de la crate traduite, les autres erreurs telles quelles
+pub fn message_erreur(erreur: &(dyn Error + 'static), langue: Langue) -> String {
+    if let Some(e) = erreur.downcast_ref::<Erreur>() {
+        return e.message(langue);
+    }
+    if let Some(e) = erreur.downcast_ref::<ErreurDate>() {
+        return e.message(langue);
+    }
+    if let Some(e) = erreur.downcast_ref::<ErreurDefinition>() {
+        return message_definition(e, langue);
+    }
+    if let Some(e) = erreur.downcast_ref::<ErreurCalque>() {
+        return e.message(langue);
+    }
+    #[cfg(feature = "image")]
+    if let Some(e) = erreur.downcast_ref::<ErreurTemplate>() {
+        return e.message(langue);
+    }
+    erreur.to_string()
+}
+
+// Erreur d'une table des positions (noyau sans catalogue)
+pub fn message_definition(erreur: &ErreurDefinition, langue: Langue) -> String {
+    let (id, nom) = match erreur {
+        ErreurDefinition::PositionInconnue(p) => ("erreur-definition-position", p),
+        ErreurDefinition::Circulaire(p) => ("erreur-definition-circulaire", p),
+        ErreurDefinition::Reference(r) => ("erreur-definition-reference", r),
+        ErreurDefinition::VarianteInconnue(r) => ("erreur-definition-variante", r),
+        ErreurDefinition::EntreeInconnue(e) => ("erreur-definition-entree", e),
+        ErreurDefinition::VarianteNonCalculee(r) => ("erreur-definition-valeur-ordre", r),
+        ErreurDefinition::VisibiliteNonCalculee(r) => ("erreur-definition-visibilite-ordre", r),
+        ErreurDefinition::PositionManquante(p) => ("erreur-definition-manquante", p),
+        ErreurDefinition::Dans(p, e) => return format!("{}: {}", p, message_definition(e, langue)),
+    };
+    message(langue, id, &[("nom", nom.clone())])
+}
+
+// Tableau texte du noyau, en-tête et étapes traduits
+impl Localise for Tableau {
+    fn fmt_langue(&self, f: &mut fmt::Formatter<'_>, langue: Langue) -> fmt::Result {
+        let date = format!("{:02}.{:02}.{:04}", self.jour, self.mois, self.annee);
+        let entete = message(langue, "rapport-naissance", &[("date", date), ("age", self.age.to_string())]);
+        let etapes = ["pulsion", "action", "reaction"].map(|e| nom_etape(e, langue));
+        self.ecrire(f, &entete, [&etapes[0], &etapes[1], &etapes[2]])
+    }
+}
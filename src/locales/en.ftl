@@ -0,0 +1,201 @@
+# English catalogue
+# Position acronyms are kept as-is when there is no established name
+
+## Errors
+
+erreur = Error: { $message }
+erreur-lecture-psd = Unable to read the PSD at '{ $chemin }': { $erreur }
+erreur-psd-invalide = Invalid PSD '{ $chemin }': { $erreur }
+erreur-psd-octets = Invalid PSD: { $erreur }
+erreur-buffer-rgba = The RGBA buffer does not match the w*h*4 dimensions
+erreur-taille-calque = Unexpected buffer size (document) for layer '{ $calque }': { $obtenu } != { $attendu }
+erreur-encodage = { $format } encoding failed: { $erreur }
+erreur-animation-vide = Animation without any image
+erreur-animation-tailles = Animation: images of different sizes
+erreur-webp-vp8l = WebP encoding failed: missing VP8L chunk
+erreur-argument = Invalid value for { $argument }: { $message }
+erreur-langue = Unsupported language: '{ $langue }' (fr, en)
+erreur-fichier-lecture = Unable to read { $chemin }: { $erreur }
+erreur-ecriture = Unable to write { $chemin }: { $erreur }
+erreur-dossier = Unable to create directory { $chemin }: { $erreur }
+erreur-json = JSON conversion failed
+erreur-json-detail = JSON conversion failed: { $erreur }
+erreur-date = Invalid date: '{ $texte }'
+erreur-annee = Invalid year: { $annee } (1 or later)
+erreur-mois = Invalid month: { $mois }
+erreur-jour = Invalid day: { $date }
+erreur-lecture-avant-naissance = The reading date { $lecture } is before the birth date { $naissance }
+erreur-age = Invalid age: { $age } (0 to { $max })
+erreur-plage-ages = Empty age range: { $de }..{ $a }
+erreur-plage-annees = Empty year range: { $de }..{ $a }
+erreur-arcane = Invalid arcana: '{ $texte }' (1 to 22)
+erreur-groupement = Invalid grouping: '{ $texte }' (expected name:group=1,2;other=3,4)
+erreur-groupe = Invalid group: '{ $texte }' (expected group=1,2)
+erreur-groupement-vide = Grouping without any group: '{ $texte }'
+erreur-contrainte = Invalid constraint: '{ $texte }' (expected POSITION.VARIANT)
+erreur-position = Unknown position: '{ $position }' ({ $positions })
+erreur-variante = Unknown variant: '{ $variante }' (PA, PB, SA, SB)
+erreur-liste = Invalid list: '{ $texte }' (expected {"{"}7, 16{"}"})
+erreur-condition = Invalid condition: '{ $texte }' (=, !=, in {"{"}..{"}"}, present, absent)
+erreur-positions-invalides = Invalid { $format } position table: { $erreur }
+erreur-positions-format = Unsupported table format: { $chemin }
+erreur-position-double = Position defined twice: { $nom }
+erreur-positions-evaluation = Invalid position table: { $erreur }
+erreur-definition-position = Unknown position: { $nom }
+erreur-definition-circulaire = Circular definition: { $nom }
+erreur-definition-reference = Invalid reference: { $nom } (POSITION.VARIANT)
+erreur-definition-variante = Unknown variant: { $nom }
+erreur-definition-entree = Unknown input: { $nom }
+erreur-definition-valeur-ordre = Variant not computed yet: { $nom }
+erreur-definition-visibilite-ordre = Visibility not computed yet: { $nom }
+erreur-definition-manquante = Position missing from the table: { $nom }
+erreur-textes-invalides = Invalid { $format } texts: { $erreur }
+erreur-textes-format = Unsupported texts format: { $chemin }
+erreur-ponderation-ligne = Weighting line { $ligne }: '{ $texte }'
+erreur-ponderation-colonnes = Weighting line { $ligne }: 2 to 4 columns expected
+erreur-modele-rapport = Invalid report template: { $erreur }
+erreur-rendu-rapport = Report rendering failed: { $erreur }
+erreur-csv-ligne = CSV line { $ligne }: { $erreur }
+erreur-calque = Invalid layer: '{ $calque }' (expected e.g. APESB14 or APESB-R)
+erreur-calque-arcane = Invalid arcana in layer '{ $calque }' (01 to 22)
+erreur-nommage-ambigu = Ambiguous naming: { $premier } and { $second } both give layer '{ $calque }'
+erreur-nommage-invalide = Invalid { $format } naming: { $erreur }
+erreur-nommage-format = Unsupported naming format: { $chemin }
+erreur-nommage-verification = Naming { $chemin }: { $erreur }
+erreur-section = Unknown section: { $section } ({ $sections })
+erreur-couleur = Invalid colour: { $couleur } (RRGGBB)
+erreur-separees-sortie = --separees: an output file is required
+erreur-separees-format = --separees: PNG or SVG only
+erreur-separees-animation = --separees: not available for an animation
+erreur-parquet-sortie = The Parquet format requires --sortie
+erreur-parquet-indisponible = Parquet export unavailable: this binary was built without the "parquet" feature (cargo install cycles_numerologie_du_tarot --features parquet)
+erreur-age-ou-lecture = Give either the age or the reading date
+erreur-template-absent = No PSD template loaded
+erreur-format-accept = Unsupported format: { $format }
+erreur-ecoute = Unable to listen on { $adresse }: { $erreur }
+
+## Positions
+
+position-CAI = CAI
+position-INT = INT
+position-CAE = CAE
+position-COI = COI
+position-COE = COE
+position-NEM = NEM
+position-PEX = Outer personality
+position-PPR = Deep personality
+
+## Cycle stages
+
+etape-pulsion = Impulse
+etape-action = Action
+etape-reaction = Reaction
+
+## Major arcana
+
+arcane-1 = The Magician
+arcane-2 = The Popess
+arcane-3 = The Empress
+arcane-4 = The Emperor
+arcane-5 = The Pope
+arcane-6 = The Lover
+arcane-7 = The Chariot
+arcane-8 = Justice
+arcane-9 = The Hermit
+arcane-10 = The Wheel of Fortune
+arcane-11 = Strength
+arcane-12 = The Hanged Man
+arcane-13 = The Nameless Arcanum
+arcane-14 = Temperance
+arcane-15 = The Devil
+arcane-16 = The Tower
+arcane-17 = The Star
+arcane-18 = The Moon
+arcane-19 = The Sun
+arcane-20 = Judgement
+arcane-21 = The World
+arcane-22 = The Fool
+
+## Report
+
+rapport-naissance = Birth: { $date }  Age: { $age }
+
+## HTML and Markdown reports
+
+libelle-titre = Tarot numerology cycles
+libelle-naissance = Birth
+libelle-age = Age
+libelle-positions = Positions
+libelle-position = Position
+libelle-arcanes = Arcana
+libelle-derivation = Derivation
+libelle-cycle = Current cycle
+libelle-analyse = Analysis
+libelle-absents = Missing arcana
+libelle-lecture = Reading
+derivation-jour = day
+derivation-mois = month
+derivation-annee = year
+derivation-age = age + 1
+
+## Analysis
+
+analyse-repetitions = Repetitions:
+analyse-aucune = none
+analyse-absents = Missing arcana: { $liste }
+analyse-aucun = none
+analyse-equilibre = balanced
+groupement-parite = Parity
+groupe-impair = Odd
+groupe-pair = Even
+groupement-actif-receptif = Active/Receptive
+groupe-actif = Active
+groupe-receptif = Receptive
+
+## Name
+
+nom-prenom = First name
+nom-nom = Last name
+nom-complet = Full name
+nom-voyelles = Vowels
+nom-consonnes = Consonants
+
+## Group
+
+constellation-groupe = Group
+constellation-partages = Shared
+constellation-manquants = Missing
+constellation-absents = Arcana missing from the group: { $liste }
+constellation-cycle = Current cycle (impulse / PPR action / PPR reaction):
+
+## Calendar
+
+ical-cycle-annuel = New yearly cycle: { $arcane } (age { $age })
+ical-mois-personnel = Personal month: { $arcane }
+ical-mois-personnel-titre = Personal month
+
+## Command line
+
+colonne-annee = Year
+colonne-actions = { $section } (act./react.)
+colonne-date = Date
+colonne-cycle-annee = Year (i. / a. / r. PPR)
+colonne-mois = Month
+colonne-jour = Day
+recherche-total = { $nombre } date(s)
+donnees-total = { $nombre } row(s)
+stats-entete = Births from { $du } to { $au }, age { $age } (total weight { $poids })
+stats-present = present
+stats-rarete = Rarity of { $naissance }: percentile { $percentile }
+lot-erreur = Line { $ligne } ({ $nom }): { $erreur }
+lot-repris = Line { $ligne } ({ $nom }): already rendered { $fichier }
+lot-rendu = Line { $ligne } ({ $nom }): { $fichier }
+lot-statut-erreur = error: { $erreur }
+lot-statut-repris = resumed
+lot-statut-ok = ok
+template-calque-manquant = Missing layer: { $calque }
+template-valide = Valid template
+
+## Server
+
+serveur-ecoute = Listening on http://{ $adresse }
@@ -0,0 +1,201 @@
+# Catalogue français (langue par défaut)
+# Les sigles des positions restent tels quels faute de nom établi
+
+## Erreurs
+
+erreur = Erreur: { $message }
+erreur-lecture-psd = Impossible de lire le PSD à '{ $chemin }': { $erreur }
+erreur-psd-invalide = PSD invalide '{ $chemin }': { $erreur }
+erreur-psd-octets = PSD invalide: { $erreur }
+erreur-buffer-rgba = Le buffer RGBA ne correspond pas aux dimensions w*h*4
+erreur-taille-calque = Taille de buffer inattendue (document) pour le calque '{ $calque }': { $obtenu } != { $attendu }
+erreur-encodage = Échec encodage { $format }: { $erreur }
+erreur-animation-vide = Animation sans image
+erreur-animation-tailles = Animation: images de tailles différentes
+erreur-webp-vp8l = Échec encodage WebP: morceau VP8L absent
+erreur-argument = Valeur invalide pour { $argument }: { $message }
+erreur-langue = Langue non supportée: '{ $langue }' (fr, en)
+erreur-fichier-lecture = Impossible de lire { $chemin }: { $erreur }
+erreur-ecriture = Erreur écriture { $chemin }: { $erreur }
+erreur-dossier = Erreur création dossier { $chemin }: { $erreur }
+erreur-json = Échec conversion JSON
+erreur-json-detail = Échec conversion JSON: { $erreur }
+erreur-date = Date invalide: '{ $texte }'
+erreur-annee = Année invalide: { $annee } (à partir de 1)
+erreur-mois = Mois invalide: { $mois }
+erreur-jour = Jour invalide: { $date }
+erreur-lecture-avant-naissance = La date de lecture { $lecture } précède la naissance { $naissance }
+erreur-age = Âge invalide: { $age } (0 à { $max })
+erreur-plage-ages = Plage d'âges vide: { $de }..{ $a }
+erreur-plage-annees = Plage d'années vide: { $de }..{ $a }
+erreur-arcane = Arcane invalide: '{ $texte }' (1 à 22)
+erreur-groupement = Groupement invalide: '{ $texte }' (attendu nom:groupe=1,2;autre=3,4)
+erreur-groupe = Groupe invalide: '{ $texte }' (attendu groupe=1,2)
+erreur-groupement-vide = Groupement sans groupe: '{ $texte }'
+erreur-contrainte = Contrainte invalide: '{ $texte }' (attendu POSITION.VARIANTE)
+erreur-position = Position inconnue: '{ $position }' ({ $positions })
+erreur-variante = Variante inconnue: '{ $variante }' (PA, PB, SA, SB)
+erreur-liste = Liste invalide: '{ $texte }' (attendu {"{"}7, 16{"}"})
+erreur-condition = Condition invalide: '{ $texte }' (=, !=, in {"{"}..{"}"}, present, absent)
+erreur-positions-invalides = Table des positions { $format } invalide: { $erreur }
+erreur-positions-format = Format de table non supporté: { $chemin }
+erreur-position-double = Position définie deux fois: { $nom }
+erreur-positions-evaluation = Table des positions invalide: { $erreur }
+erreur-definition-position = Position inconnue: { $nom }
+erreur-definition-circulaire = Définition circulaire: { $nom }
+erreur-definition-reference = Référence invalide: { $nom } (POSITION.VARIANTE)
+erreur-definition-variante = Variante inconnue: { $nom }
+erreur-definition-entree = Entrée inconnue: { $nom }
+erreur-definition-valeur-ordre = Variante pas encore calculée: { $nom }
+erreur-definition-visibilite-ordre = Visibilité pas encore calculée: { $nom }
+erreur-definition-manquante = Position manquante dans la table: { $nom }
+erreur-textes-invalides = Textes { $format } invalides: { $erreur }
+erreur-textes-format = Format de textes non supporté: { $chemin }
+erreur-ponderation-ligne = Pondération ligne { $ligne }: '{ $texte }'
+erreur-ponderation-colonnes = Pondération ligne { $ligne }: 2 à 4 colonnes attendues
+erreur-modele-rapport = Modèle de rapport invalide: { $erreur }
+erreur-rendu-rapport = Échec du rendu du rapport: { $erreur }
+erreur-csv-ligne = CSV ligne { $ligne }: { $erreur }
+erreur-calque = Calque invalide: '{ $calque }' (attendu ex: APESB14 ou APESB-R)
+erreur-calque-arcane = Arcane invalide dans le calque '{ $calque }' (01 à 22)
+erreur-nommage-ambigu = Nommage ambigu: { $premier } et { $second } donnent le calque '{ $calque }'
+erreur-nommage-invalide = Nommage { $format } invalide: { $erreur }
+erreur-nommage-format = Format de nommage non supporté: { $chemin }
+erreur-nommage-verification = Nommage { $chemin }: { $erreur }
+erreur-section = Section inconnue: { $section } ({ $sections })
+erreur-couleur = Couleur invalide: { $couleur } (RRGGBB)
+erreur-separees-sortie = --separees: fichier de sortie requis
+erreur-separees-format = --separees: PNG ou SVG seulement
+erreur-separees-animation = --separees: non disponible pour une animation
+erreur-parquet-sortie = Le format Parquet nécessite --sortie
+erreur-parquet-indisponible = Export Parquet indisponible: ce binaire est compilé sans la feature "parquet" (cargo install cycles_numerologie_du_tarot --features parquet)
+erreur-age-ou-lecture = Donner soit l'âge, soit la date de lecture
+erreur-template-absent = Aucun template PSD chargé
+erreur-format-accept = Format non supporté: { $format }
+erreur-ecoute = Impossible d'écouter sur { $adresse }: { $erreur }
+
+## Positions
+
+position-CAI = CAI
+position-INT = INT
+position-CAE = CAE
+position-COI = COI
+position-COE = COE
+position-NEM = NEM
+position-PEX = Personnalité extérieure
+position-PPR = Personnalité profonde
+
+## Étapes du cycle
+
+etape-pulsion = Pulsion
+etape-action = Action
+etape-reaction = Réaction
+
+## Arcanes majeurs
+
+arcane-1 = Le Bateleur
+arcane-2 = La Papesse
+arcane-3 = L'Impératrice
+arcane-4 = L'Empereur
+arcane-5 = Le Pape
+arcane-6 = L'Amoureux
+arcane-7 = Le Chariot
+arcane-8 = La Justice
+arcane-9 = L'Hermite
+arcane-10 = La Roue de Fortune
+arcane-11 = La Force
+arcane-12 = Le Pendu
+arcane-13 = L'Arcane sans nom
+arcane-14 = Tempérance
+arcane-15 = Le Diable
+arcane-16 = La Maison Dieu
+arcane-17 = L'Étoile
+arcane-18 = La Lune
+arcane-19 = Le Soleil
+arcane-20 = Le Jugement
+arcane-21 = Le Monde
+arcane-22 = Le Mat
+
+## Rapport
+
+rapport-naissance = Naissance: { $date }  Âge: { $age }
+
+## Rapports HTML et Markdown
+
+libelle-titre = Cycles de numérologie du tarot
+libelle-naissance = Naissance
+libelle-age = Âge
+libelle-positions = Positions
+libelle-position = Position
+libelle-arcanes = Arcanes
+libelle-derivation = Calcul
+libelle-cycle = Cycle en cours
+libelle-analyse = Analyse
+libelle-absents = Arcanes absents
+libelle-lecture = Lecture
+derivation-jour = jour
+derivation-mois = mois
+derivation-annee = année
+derivation-age = âge + 1
+
+## Analyse
+
+analyse-repetitions = Répétitions:
+analyse-aucune = aucune
+analyse-absents = Arcanes absents: { $liste }
+analyse-aucun = aucun
+analyse-equilibre = équilibre
+groupement-parite = Parité
+groupe-impair = Impair
+groupe-pair = Pair
+groupement-actif-receptif = Actif/Réceptif
+groupe-actif = Actif
+groupe-receptif = Réceptif
+
+## Nom
+
+nom-prenom = Prénom
+nom-nom = Nom
+nom-complet = Nom complet
+nom-voyelles = Voyelles
+nom-consonnes = Consonnes
+
+## Groupe
+
+constellation-groupe = Groupe
+constellation-partages = Partagés
+constellation-manquants = Manquants
+constellation-absents = Arcanes absents du groupe: { $liste }
+constellation-cycle = Cycle en cours (pulsion / action PPR / réaction PPR):
+
+## Calendrier
+
+ical-cycle-annuel = Nouveau cycle annuel: { $arcane } ({ $age } ans)
+ical-mois-personnel = Mois personnel: { $arcane }
+ical-mois-personnel-titre = Mois personnel
+
+## Ligne de commande
+
+colonne-annee = Année
+colonne-actions = { $section } (act./réact.)
+colonne-date = Date
+colonne-cycle-annee = Année (p. / a. / r. PPR)
+colonne-mois = Mois
+colonne-jour = Jour
+recherche-total = { $nombre } date(s)
+donnees-total = { $nombre } ligne(s)
+stats-entete = Naissances du { $du } au { $au }, âge { $age } (poids total { $poids })
+stats-present = présent
+stats-rarete = Rareté de { $naissance }: percentile { $percentile }
+lot-erreur = Ligne { $ligne } ({ $nom }): { $erreur }
+lot-repris = Ligne { $ligne } ({ $nom }): déjà rendu { $fichier }
+lot-rendu = Ligne { $ligne } ({ $nom }): { $fichier }
+lot-statut-erreur = erreur: { $erreur }
+lot-statut-repris = repris
+lot-statut-ok = ok
+template-calque-manquant = Calque manquant: { $calque }
+template-valide = Template valide
+
+## Serveur
+
+serveur-ecoute = Écoute sur http://{ $adresse }
@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::date::{verifier_age, ErreurDate};
+use crate::langue::{message, message_erreur, Erreur, Langue};
+use crate::tableau::NOMS_POSITIONS;
+use crate::{calcul, Date, Tableau, Template};
+
+// Journal des lignes terminées, pour reprendre un lot interrompu
+const JOURNAL: &str = ".cycles_lot_journal";
+
+/************************************
+ * Un client du fichier CSV          *
+ * nom,jour,mois,annee[,age][,lecture] *
+ ************************************/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Client {
+    pub ligne: usize,
+    pub nom: String,
+    pub naissance: Date,
+    pub age: Option<usize>,
+    pub lecture: Option<Date>,
+}
+
+#[derive(Deserialize)]
+struct LigneCsv {
+    #[serde(alias = "name")]
+    nom: String,
+    #[serde(alias = "day")]
+    jour: usize,
+    #[serde(alias = "month")]
+    mois: usize,
+    #[serde(alias = "year")]
+    annee: usize,
+    #[serde(default)]
+    age: Option<usize>,
+    #[serde(default, alias = "reading_date")]
+    lecture: Option<String>,
+}
+
+impl Client {
+    // Âge explicite, sinon âge à la date de lecture du client ou du lot
+    pub fn age(&self, lecture_defaut: &Date) -> Result<usize, ErreurDate> {
+        if let Some(age) = self.age {
+            return verifier_age(age);
+        }
+        self.naissance.age_le(&self.lecture.unwrap_or(*lecture_defaut))
+    }
+}
+
+pub fn lire_clients(path_csv: &str) -> Result<Vec<Client>, Box<dyn Error>> {
+    let mut lecteur = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(path_csv)
+        .map_err(|e| Erreur::new("erreur-fichier-lecture", &[("chemin", path_csv.to_string())]).avec_cause(e))?;
+    let mut clients = vec![];
+    for (i, ligne) in lecteur.deserialize::<LigneCsv>().enumerate() {
+        let ligne_no = i + 1;
+        let erreur_ligne = |e: Box<dyn Error + Send + Sync>| Erreur::new("erreur-csv-ligne", &[("ligne", ligne_no.to_string())]).avec_cause(e);
+        let ligne = ligne.map_err(|e| erreur_ligne(e.into()))?;
+        let naissance = Date::new(ligne.jour, ligne.mois, ligne.annee).map_err(|e| erreur_ligne(e.into()))?;
+        let lecture = match ligne.lecture.as_deref() {
+            None | Some("") => None,
+            Some(s) => Some(s.parse::<Date>().map_err(|e| erreur_ligne(e.into()))?),
+        };
+        clients.push(Client { ligne: ligne_no, nom: ligne.nom, naissance, age: ligne.age, lecture });
+    }
+    Ok(clients)
+}
+
+/***************************************************
+ * Options du lot                                  *
+ * modele_nom: {ligne} {nom} {jour} {mois} {annee} *
+ *             {age}                               *
+ * langue: des erreurs et statuts du résumé        *
+ ***************************************************/
+#[derive(Debug, Clone)]
+pub struct OptionsLot {
+    pub dossier: PathBuf,
+    pub modele_nom: String,
+    pub lecture: Date,
+    pub reprendre: bool,
+    pub langue: Langue,
+}
+
+impl OptionsLot {
+    pub fn new<P: Into<PathBuf>>(dossier: P) -> OptionsLot {
+        OptionsLot {
+            dossier: dossier.into(),
+            modele_nom: "{ligne}_{nom}_{jour}-{mois}-{annee}.png".to_string(),
+            lecture: Date::aujourd_hui(),
+            reprendre: false,
+            langue: Langue::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultatLot {
+    pub ligne: usize,
+    pub nom: String,
+    pub naissance: Date,
+    pub fichier: String,
+    pub repris: bool,
+    pub erreur: Option<String>,
+    pub tableau: Option<Tableau>,
+}
+
+pub fn nom_fichier(modele: &str, client: &Client, age: usize) -> String {
+    let nom: String = client
+        .nom
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    modele
+        .replace("{ligne}", &client.ligne.to_string())
+        .replace("{nom}", &nom)
+        .replace("{jour}", &format!("{:02}", client.naissance.jour))
+        .replace("{mois}", &format!("{:02}", client.naissance.mois))
+        .replace("{annee}", &client.naissance.annee.to_string())
+        .replace("{age}", &age.to_string())
+}
+
+/****************************************************
+ * Rendu du lot en parallèle                        *
+ * Chaque PNG terminé est inscrit dans le journal;  *
+ * avec reprendre = true les lignes déjà inscrites  *
+ * (et dont le fichier existe) ne sont pas refaites *
+ ****************************************************/
+pub fn rendre_lot(template: &Template, clients: &[Client], options: &OptionsLot) -> Result<Vec<ResultatLot>, Box<dyn Error>> {
+    rendre_lot_avec(clients, options, |n, age| template.rendre(n.jour, n.mois, n.annee, age))
+}
+
+// Même lot, PNG produit par rendre (naissance, âge)
+pub fn rendre_lot_avec<F>(clients: &[Client], options: &OptionsLot, rendre: F) -> Result<Vec<ResultatLot>, Box<dyn Error>>
+where
+    F: Fn(Date, usize) -> Result<Vec<u8>, Box<dyn Error>> + Sync,
+{
+    fs::create_dir_all(&options.dossier)
+        .map_err(|e| Erreur::new("erreur-dossier", &[("chemin", options.dossier.display().to_string())]).avec_cause(e))?;
+    let path_journal = options.dossier.join(JOURNAL);
+    let termines = if options.reprendre { lire_journal(&path_journal)? } else { HashSet::new() };
+    let journal = Mutex::new(
+        OpenOptions::new()
+            .create(true)
+            .append(options.reprendre)
+            .write(true)
+            .truncate(!options.reprendre)
+            .open(&path_journal)?,
+    );
+    let resultats: Vec<ResultatLot> = clients
+        .par_iter()
+        .map(|client| {
+            let age = match client.age(&options.lecture) {
+                Ok(age) => age,
+                Err(e) => return resultat(client, String::new(), None, Some(e.message(options.langue))),
+            };
+            let n = client.naissance;
+            let tableau = calcul(n.jour, n.mois, n.annee, age);
+            let fichier = nom_fichier(&options.modele_nom, client, age);
+            let path = options.dossier.join(&fichier);
+            if termines.contains(&(client.ligne, fichier.clone())) && path.exists() {
+                let mut r = resultat(client, fichier, Some(tableau), None);
+                r.repris = true;
+                return r;
+            }
+            match rendre_client(&rendre, &path, n, age) {
+                Ok(()) => {
+                    if let Ok(mut j) = journal.lock() {
+                        let _ = writeln!(j, "{}\t{}", client.ligne, fichier).and_then(|_| j.flush());
+                    }
+                    resultat(client, fichier, Some(tableau), None)
+                },
+                Err(e) => resultat(client, fichier, Some(tableau), Some(message_erreur(e.as_ref(), options.langue))),
+            }
+        })
+        .collect();
+    ecrire_resume(&options.dossier, &resultats, options.langue)?;
+    Ok(resultats)
+}
+
+fn resultat(client: &Client, fichier: String, tableau: Option<Tableau>, erreur: Option<String>) -> ResultatLot {
+    ResultatLot {
+        ligne: client.ligne,
+        nom: client.nom.clone(),
+        naissance: client.naissance,
+        fichier,
+        repris: false,
+        erreur,
+        tableau,
+    }
+}
+
+// Écriture dans un fichier temporaire puis renommage: un PNG interrompu n'est jamais pris pour terminé
+fn rendre_client(rendre: impl Fn(Date, usize) -> Result<Vec<u8>, Box<dyn Error>>, path: &Path, n: Date, age: usize) -> Result<(), Box<dyn Error>> {
+    let png = rendre(n, age)?;
+    let tmp = path.with_extension("part");
+    let erreur = |p: &Path, e: std::io::Error| Erreur::new("erreur-ecriture", &[("chemin", p.display().to_string())]).avec_cause(e);
+    fs::write(&tmp, &png).map_err(|e| erreur(&tmp, e))?;
+    fs::rename(&tmp, path).map_err(|e| erreur(path, e))?;
+    Ok(())
+}
+
+fn lire_journal(path: &Path) -> Result<HashSet<(usize, String)>, Box<dyn Error>> {
+    let mut termines = HashSet::new();
+    if !path.exists() {
+        return Ok(termines);
+    }
+    for ligne in BufReader::new(File::open(path)?).lines() {
+        let ligne = ligne?;
+        if let Some((no, fichier)) = ligne.split_once('\t')
+            && let Ok(no) = no.parse::<usize>() {
+            termines.insert((no, fichier.to_string()));
+        }
+    }
+    Ok(termines)
+}
+
+/*****************************************
+ * Résumé du lot: resume.json et         *
+ * resume.csv (une colonne par variante) *
+ *****************************************/
+fn ecrire_resume(dossier: &Path, resultats: &[ResultatLot], langue: Langue) -> Result<(), Box<dyn Error>> {
+    fs::write(dossier.join("resume.json"), serde_json::to_string_pretty(resultats)?)?;
+    let mut w = csv::Writer::from_path(dossier.join("resume.csv"))?;
+    let mut entete: Vec<String> = ["ligne", "nom", "naissance", "age", "fichier", "statut"]
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+    for (nom, _) in colonnes(None) {
+        entete.push(nom);
+    }
+    w.write_record(&entete)?;
+    for r in resultats.iter() {
+        let statut = match (&r.erreur, r.repris) {
+            (Some(e), _) => message(langue, "lot-statut-erreur", &[("erreur", e.clone())]),
+            (None, true) => message(langue, "lot-statut-repris", &[]),
+            (None, false) => message(langue, "lot-statut-ok", &[]),
+        };
+        let mut ligne = vec![
+            r.ligne.to_string(),
+            r.nom.clone(),
+            r.naissance.to_string(),
+            r.tableau.map(|t| t.age.to_string()).unwrap_or_default(),
+            r.fichier.clone(),
+            statut,
+        ];
+        for (_, valeur) in colonnes(r.tableau.as_ref()) {
+            ligne.push(valeur);
+        }
+        w.write_record(&ligne)?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+// (PPR_PA, "4"), ... vide si la variante n'est pas visible
+fn colonnes(tableau: Option<&Tableau>) -> Vec<(String, String)> {
+    let mut colonnes = vec![];
+    for (i, nom) in NOMS_POSITIONS.iter().enumerate() {
+        for (j, variante) in ["PA", "PB", "SA", "SB"].iter().enumerate() {
+            let valeur = tableau
+                .and_then(|t| t.positions()[i].1.variantes()[j].1.visible())
+                .map(|x| x.to_string())
+                .unwrap_or_default();
+            colonnes.push((format!("{}_{}", nom, variante), valeur));
+        }
+    }
+    colonnes
+}
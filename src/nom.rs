@@ -0,0 +1,214 @@
+use std::fmt;
+use crate::calque::{LayerId, VarianteCalque};
+use crate::langue::{message, Langue, Localise};
+use crate::reduction_theosophique;
+use crate::rendu::{Couche, OptionsRendu};
+use crate::tableau::{position_pa_sa, Position};
+use crate::visibilite::VarianteVue;
+
+/**************************************************
+ * Table de correspondance lettres -> nombres     *
+ * valeurs[0] = A ... valeurs[25] = Z             *
+ **************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableLettres {
+    pub valeurs: [usize; 26],
+    pub voyelles: Vec<char>,
+}
+
+impl TableLettres {
+    pub fn new(valeurs: [usize; 26]) -> TableLettres {
+        TableLettres { valeurs, voyelles: "AEIOUY".chars().collect() }
+    }
+
+    // A=1 ... I=9, J=1 ... R=9, S=1 ... Z=8
+    pub fn pythagoricienne() -> TableLettres {
+        TableLettres::new(std::array::from_fn(|i| i % 9 + 1))
+    }
+
+    // Chaldéenne: valeurs 1 à 8 d'après le son, jamais 9
+    pub fn chaldeenne() -> TableLettres {
+        TableLettres::new([1, 2, 3, 4, 5, 8, 3, 5, 1, 1, 2, 3, 4, 5, 7, 8, 1, 2, 3, 4, 6, 6, 6, 5, 1, 7])
+    }
+
+    // A=1 ... Z=26 (réduit ensuite à 22)
+    pub fn alphabetique() -> TableLettres {
+        TableLettres::new(std::array::from_fn(|i| i + 1))
+    }
+
+    pub fn valeur(&self, lettre: char) -> Option<usize> {
+        let lettre = lettre.to_ascii_uppercase();
+        if lettre.is_ascii_uppercase() {
+            Some(self.valeurs[(lettre as u8 - b'A') as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn est_voyelle(&self, lettre: char) -> bool {
+        self.voyelles.contains(&lettre.to_ascii_uppercase())
+    }
+}
+
+impl Default for TableLettres {
+    fn default() -> TableLettres {
+        TableLettres::pythagoricienne()
+    }
+}
+
+/*********************************************
+ * Majuscules sans accents: "Zoé Lefèvre-Œ"  *
+ * -> "ZOE LEFEVRE-OE"                       *
+ *********************************************/
+pub fn sans_accents(texte: &str) -> String {
+    let mut resultat = String::with_capacity(texte.len());
+    for c in texte.chars().flat_map(|c| c.to_uppercase()) {
+        match c {
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => resultat.push('A'),
+            'Æ' => resultat.push_str("AE"),
+            'Ç' => resultat.push('C'),
+            'È' | 'É' | 'Ê' | 'Ë' => resultat.push('E'),
+            'Ì' | 'Í' | 'Î' | 'Ï' => resultat.push('I'),
+            'Ñ' => resultat.push('N'),
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => resultat.push('O'),
+            'Œ' => resultat.push_str("OE"),
+            'Ù' | 'Ú' | 'Û' | 'Ü' => resultat.push('U'),
+            'Ý' | 'Ÿ' => resultat.push('Y'),
+            'ß' => resultat.push_str("SS"),
+            _ => resultat.push(c),
+        }
+    }
+    resultat
+}
+
+/*************************************************
+ * Sommes brutes d'un texte (avant réduction)    *
+ * les caractères hors A-Z sont ignorés          *
+ *************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Sommes {
+    pub total: usize,
+    pub voyelles: usize,
+    pub consonnes: usize,
+}
+
+pub fn sommes(texte: &str, table: &TableLettres) -> Sommes {
+    let mut s = Sommes::default();
+    for lettre in sans_accents(texte).chars() {
+        if let Some(v) = table.valeur(lettre) {
+            s.total += v;
+            if table.est_voyelle(lettre) {
+                s.voyelles += v;
+            } else {
+                s.consonnes += v;
+            }
+        }
+    }
+    s
+}
+
+/**************************************************
+ * Positions issues du nom, même forme que les    *
+ * positions du tableau: PA réduit à 22, SA       *
+ * réduit à 9 (visible s'il diffère de PA)        *
+ **************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumerologieNom {
+    pub prenom: Position,
+    pub nom: Position,
+    pub complet: Position,
+    pub voyelles: Position,
+    pub consonnes: Position,
+}
+
+fn position(somme: usize) -> Position {
+    let pa = reduction_theosophique(somme, false);
+    let sa = reduction_theosophique(pa, true);
+    position_pa_sa(pa, sa, pa != sa)
+}
+
+pub fn numerologie_nom(prenom: &str, nom: &str, table: &TableLettres) -> NumerologieNom {
+    let p = sommes(prenom, table);
+    let n = sommes(nom, table);
+    NumerologieNom {
+        prenom: position(p.total),
+        nom: position(n.total),
+        complet: position(p.total + n.total),
+        voyelles: position(p.voyelles + n.voyelles),
+        consonnes: position(p.consonnes + n.consonnes),
+    }
+}
+
+impl NumerologieNom {
+    // Positions supplémentaires avec le préfixe de leurs calques Photoshop
+    pub fn sections(&self) -> [(&'static str, Position); 5] {
+        [
+            ("PRE", self.prenom),
+            ("NOM", self.nom),
+            ("NCO", self.complet),
+            ("VOY", self.voyelles),
+            ("CON", self.consonnes),
+        ]
+    }
+}
+
+/*************************************************
+ * Calques des positions du nom: valeurs et      *
+ * leurs cadres, à ajouter à ceux du tableau     *
+ * Politique de visibilité et couches des        *
+ * options, chaque position du nom étant une     *
+ * section (hors du filtre des sections, propre  *
+ * à la table)                                   *
+ *************************************************/
+pub fn calques_nom(nom: &NumerologieNom, options: &OptionsRendu) -> Vec<String> {
+    ids_calques_nom(nom, options).iter().map(|c| c.to_string()).collect()
+}
+
+pub fn ids_calques_nom(nom: &NumerologieNom, options: &OptionsRendu) -> Vec<LayerId> {
+    let sections = nom.sections().map(|(prefixe, position)| {
+        let mut couches: [Vec<LayerId>; 4] = Default::default();
+        for (variante, (v, arcane)) in VarianteCalque::TOUTES.into_iter().zip(position.variantes()) {
+            // PA et SA seuls dessinables, comme les positions PA/SA de la table
+            let vue = VarianteVue { position: prefixe, variante: v, arcane, dessinable: !variante.est_b() };
+            if !options.visibilite.visible(&vue) {
+                continue;
+            }
+            let (cadres, valeurs) = if variante.est_b() { (Couche::CadresB, Couche::ValeursB) } else { (Couche::CadresA, Couche::ValeursA) };
+            let valeur = LayerId::valeur(prefixe, variante, arcane.valeur);
+            couches[cadres as usize].extend(valeur.cadre());
+            couches[valeurs as usize].push(valeur);
+        }
+        couches
+    });
+    options.empiler(sections.to_vec())
+}
+
+impl Localise for NumerologieNom {
+    fn fmt_langue(&self, f: &mut fmt::Formatter<'_>, langue: Langue) -> fmt::Result {
+        for (nom, p) in [
+            ("nom-prenom", &self.prenom),
+            ("nom-nom", &self.nom),
+            ("nom-complet", &self.complet),
+            ("nom-voyelles", &self.voyelles),
+            ("nom-consonnes", &self.consonnes),
+        ] {
+            write!(f, "{:<12}", message(langue, nom, &[]))?;
+            for (_, arcane) in p.variantes() {
+                match arcane.visible() {
+                    Some(x) => write!(f, " {:>4}", x)?,
+                    None => write!(f, " {:>4}", "-")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NumerologieNom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_langue(f, Langue::default())
+    }
+}
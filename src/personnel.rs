@@ -0,0 +1,79 @@
+use crate::tableau::{cycles, Cycle};
+use crate::{calcul, reduction_theosophique, Date, Tableau};
+
+/****************************************************
+ * Cycles personnels d'un jour du calendrier        *
+ * Année: pulsion = âge + 1 (comme calcl)           *
+ * Mois: pulsion = pulsion de l'année + mois        *
+ * Jour: pulsion = pulsion du mois + jour           *
+ * Action et réaction dérivées comme pour l'année,  *
+ * pour chaque variante visible de PPR, NEM et PEX  *
+ ****************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CyclesSections {
+    pub ppr: Cycle,
+    pub nem: Cycle,
+    pub pex: Cycle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CyclesPersonnels {
+    pub date: Date,
+    pub age: usize,
+    pub annee: CyclesSections,
+    pub mois: CyclesSections,
+    pub jour: CyclesSections,
+}
+
+fn sections(tableau: &Tableau, pulsion: usize) -> CyclesSections {
+    let [ppr, nem, pex] = cycles(pulsion, &tableau.ppr, &tableau.nem, &tableau.pex);
+    CyclesSections { ppr, nem, pex }
+}
+
+// None si la date précède la naissance
+pub fn cycles_personnels(naissance: &Date, date: &Date) -> Option<CyclesPersonnels> {
+    let age = naissance.age_au(date)?;
+    let tableau = calcul(naissance.jour, naissance.mois, naissance.annee, age);
+    let pulsion_annee = tableau.cycle_ppr.pulsion.pa.valeur;
+    let pulsion_mois = reduction_theosophique(pulsion_annee + date.mois, false);
+    let pulsion_jour = reduction_theosophique(pulsion_mois + date.jour, false);
+    Some(CyclesPersonnels {
+        date: *date,
+        age,
+        annee: CyclesSections { ppr: tableau.cycle_ppr, nem: tableau.cycle_nem, pex: tableau.cycle_pex },
+        mois: sections(&tableau, pulsion_mois),
+        jour: sections(&tableau, pulsion_jour),
+    })
+}
+
+/**********************************************
+ * Itérateur jour par jour, bornes incluses   *
+ * (les jours avant la naissance sont sautés) *
+ **********************************************/
+pub struct IterCyclesPersonnels {
+    naissance: Date,
+    courant: Date,
+    fin: Date,
+}
+
+impl Iterator for IterCyclesPersonnels {
+    type Item = CyclesPersonnels;
+
+    fn next(&mut self) -> Option<CyclesPersonnels> {
+        if self.courant < self.naissance {
+            self.courant = self.naissance;
+        }
+        if self.courant > self.fin {
+            return None;
+        }
+        let date = self.courant;
+        self.courant = date.lendemain();
+        cycles_personnels(&self.naissance, &date)
+    }
+}
+
+pub fn cycles_entre(naissance: &Date, debut: &Date, fin: &Date) -> IterCyclesPersonnels {
+    IterCyclesPersonnels { naissance: *naissance, courant: *debut, fin: *fin }
+}
@@ -0,0 +1,138 @@
+use std::error::Error;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use crate::date::{plage_ages, verifier_age};
+use crate::langue::{message_erreur, Erreur};
+use crate::{calcl, calcul, chronologie as chronologie_rs, reduction_theosophique as reduction_rs, Date, Langue, OptionsRendu, Template};
+
+/*****************************************************
+ * Liaisons Python (feature "python")                *
+ * maturin build --release (voir pyproject.toml)     *
+ * Les tableaux sont des dict (même forme que le     *
+ * JSON de la commande cycles compute)               *
+ * langue: "fr" (défaut) ou "en", pour les erreurs   *
+ *****************************************************/
+
+fn erreur(e: impl Into<Box<dyn Error>>, langue: Langue) -> PyErr {
+    PyValueError::new_err(message_erreur(e.into().as_ref(), langue))
+}
+
+fn langue(langue: Option<&str>) -> PyResult<Langue> {
+    langue.map_or(Ok(Langue::default()), |l| l.parse().map_err(|e| erreur(e, Langue::default())))
+}
+
+fn date(s: &str, langue: Langue) -> PyResult<Date> {
+    s.parse::<Date>().map_err(|e| erreur(e, langue))
+}
+
+fn age(naissance: &Date, lecture: Option<&str>, age: Option<usize>, langue: Langue) -> PyResult<usize> {
+    if let Some(age) = age {
+        return verifier_age(age).map_err(|e| erreur(e, langue));
+    }
+    let lecture = match lecture {
+        Some(s) => date(s, langue)?,
+        None => Date::aujourd_hui(),
+    };
+    naissance.age_le(&lecture).map_err(|e| erreur(e, langue))
+}
+
+fn objet_py<'py, T: serde::Serialize>(py: Python<'py>, valeur: &T) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(valeur).map_err(|e| erreur(Erreur::new("erreur-json-detail", &[]).avec_cause(e), Langue::default()))?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+#[pyfunction]
+#[pyo3(signature = (n, sw_9=false))]
+fn reduction_theosophique(n: usize, sw_9: bool) -> usize {
+    reduction_rs(n, sw_9)
+}
+
+// calculer("14.06.1946", lecture="2025-10-18") ou calculer("14.06.1946", age=79, langue="en")
+#[pyfunction]
+#[pyo3(signature = (naissance, lecture=None, age=None, langue=None))]
+fn calculer<'py>(py: Python<'py>, naissance: &str, lecture: Option<&str>, age: Option<usize>, langue: Option<&str>) -> PyResult<Bound<'py, PyAny>> {
+    let langue = self::langue(langue)?;
+    let n = date(naissance, langue)?;
+    let age = self::age(&n, lecture, age, langue)?;
+    objet_py(py, &calcul(n.jour, n.mois, n.annee, age))
+}
+
+#[pyfunction]
+#[pyo3(signature = (naissance, de, a, langue=None))]
+fn chronologie<'py>(py: Python<'py>, naissance: &str, de: usize, a: usize, langue: Option<&str>) -> PyResult<Bound<'py, PyAny>> {
+    let langue = self::langue(langue)?;
+    let n = date(naissance, langue)?;
+    let ages = plage_ages(de, a).map_err(|e| erreur(e, langue))?;
+    objet_py(py, &chronologie_rs(n.jour, n.mois, n.annee, ages))
+}
+
+// Noms des calques Photoshop, dans l'ordre de superposition
+#[pyfunction]
+fn calques(j: usize, m: usize, a: usize, age: usize) -> Vec<String> {
+    calcl(j, m, a, age, &OptionsRendu::default()).iter().map(|c| c.to_string()).collect()
+}
+
+/***************************************************
+ * Template PSD décodé une fois                    *
+ * rendre_rgba -> (largeur, hauteur, octets) pour  *
+ * PIL.Image.frombytes("RGBA", (l, h), octets)     *
+ ***************************************************/
+#[pyclass(name = "Template")]
+struct TemplatePy {
+    template: Template,
+}
+
+#[pymethods]
+impl TemplatePy {
+    #[new]
+    #[pyo3(signature = (psd, langue=None))]
+    fn new(psd: &[u8], langue: Option<&str>) -> PyResult<TemplatePy> {
+        let langue = self::langue(langue)?;
+        Ok(TemplatePy { template: Template::depuis_octets(psd).map_err(|e| erreur(e, langue))? })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (path_psd, langue=None))]
+    fn ouvrir(path_psd: &str, langue: Option<&str>) -> PyResult<TemplatePy> {
+        let langue = self::langue(langue)?;
+        Ok(TemplatePy { template: Template::ouvrir(path_psd).map_err(|e| erreur(e, langue))? })
+    }
+
+    fn calques_manquants(&self) -> Vec<String> {
+        self.template.calques_manquants()
+    }
+
+    #[pyo3(signature = (naissance, lecture=None, age=None, langue=None))]
+    fn rendre<'py>(&self, py: Python<'py>, naissance: &str, lecture: Option<&str>, age: Option<usize>, langue: Option<&str>) -> PyResult<Bound<'py, PyBytes>> {
+        let langue = self::langue(langue)?;
+        let n = date(naissance, langue)?;
+        let age = self::age(&n, lecture, age, langue)?;
+        let png = py
+            .detach(|| self.template.rendre(n.jour, n.mois, n.annee, age).map_err(|e| message_erreur(e.as_ref(), langue)))
+            .map_err(PyValueError::new_err)?;
+        Ok(PyBytes::new(py, &png))
+    }
+
+    #[pyo3(signature = (naissance, lecture=None, age=None, langue=None))]
+    fn rendre_rgba<'py>(&self, py: Python<'py>, naissance: &str, lecture: Option<&str>, age: Option<usize>, langue: Option<&str>) -> PyResult<(u32, u32, Bound<'py, PyBytes>)> {
+        let langue = self::langue(langue)?;
+        let n = date(naissance, langue)?;
+        let age = self::age(&n, lecture, age, langue)?;
+        let image = py
+            .detach(|| self.template.image(n.jour, n.mois, n.annee, age).map_err(|e| message_erreur(e.as_ref(), langue)))
+            .map_err(PyValueError::new_err)?;
+        let (l, h) = image.dimensions();
+        Ok((l, h, PyBytes::new(py, image.as_raw())))
+    }
+}
+
+#[pymodule]
+fn cycles_numerologie_du_tarot(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(reduction_theosophique, m)?)?;
+    m.add_function(wrap_pyfunction!(calculer, m)?)?;
+    m.add_function(wrap_pyfunction!(chronologie, m)?)?;
+    m.add_function(wrap_pyfunction!(calques, m)?)?;
+    m.add_class::<TemplatePy>()?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "image")]
+use std::error::Error;
+use serde::Serialize;
+use crate::analyse::{analyser_langue, Analyse, Groupement};
+use crate::interpretation::Lecture;
+use crate::langue::{message, nom_arcane_langue, nom_position, Erreur, Langue};
+use crate::tableau::{Cycle, Position};
+use crate::{reduction_theosophique, Tableau};
+
+/*****************************************************
+ * Rapport de lecture HTML ou Markdown, rendu par    *
+ * un modèle minijinja (Jinja2) modifiable à partir  *
+ * du tableau calculé. Modèles par défaut dans       *
+ * src/rapports                                      *
+ *****************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatRapport {
+    Html,
+    Markdown,
+}
+
+impl FormatRapport {
+    pub fn modele_par_defaut(&self) -> &'static str {
+        match self {
+            FormatRapport::Html => include_str!("rapports/rapport.html.j2"),
+            FormatRapport::Markdown => include_str!("rapports/rapport.md.j2"),
+        }
+    }
+
+    // L'extension décide de l'échappement automatique de minijinja
+    fn nom_modele(&self) -> &'static str {
+        match self {
+            FormatRapport::Html => "rapport.html",
+            FormatRapport::Markdown => "rapport.md",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArcaneRapport {
+    pub variante: String,
+    pub valeur: usize,
+    pub nom: String,
+}
+
+// Variantes visibles d'une position, un arcane répété n'apparaît qu'une fois
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PositionRapport {
+    pub code: String,
+    pub nom: String,
+    pub arcanes: Vec<ArcaneRapport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CycleRapport {
+    pub section: String,
+    pub pulsion: PositionRapport,
+    pub action: PositionRapport,
+    pub reaction: PositionRapport,
+}
+
+// Calcul d'une variante PA: somme des opérandes puis réduction à 22
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Etape {
+    pub position: String,
+    pub formule: String,
+    pub operandes: Vec<usize>,
+    pub somme: usize,
+    pub resultat: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContexteRapport {
+    pub langue: String,
+    // Absente pour un tableau combiné
+    pub naissance: Option<String>,
+    pub age: usize,
+    // PNG en data URI, ou SVG (PNG enveloppé) à insérer tel quel
+    pub image: Option<String>,
+    pub svg: Option<String>,
+    pub positions: Vec<PositionRapport>,
+    pub cycle: Vec<CycleRapport>,
+    pub derivation: Vec<Etape>,
+    pub analyse: Analyse,
+    pub lecture: Option<Lecture>,
+    // Titres traduits, ex: libelles.positions
+    pub libelles: BTreeMap<String, String>,
+}
+
+const LIBELLES: [&str; 11] = [
+    "titre", "naissance", "age", "positions", "position", "arcanes", "derivation", "cycle", "analyse", "absents", "lecture",
+];
+
+fn position(code: &str, p: &Position, langue: Langue) -> PositionRapport {
+    let mut arcanes: Vec<ArcaneRapport> = vec![];
+    for (variante, arcane) in p.variantes() {
+        if let Some(x) = arcane.visible().filter(|x| !arcanes.iter().any(|a| a.valeur == *x)) {
+            arcanes.push(ArcaneRapport { variante: variante.to_string(), valeur: x, nom: nom_arcane_langue(x, langue).unwrap_or_default() });
+        }
+    }
+    PositionRapport { code: code.to_string(), nom: nom_position(code, langue), arcanes }
+}
+
+fn cycle(section: &str, c: &Cycle, langue: Langue) -> CycleRapport {
+    CycleRapport {
+        section: section.to_string(),
+        pulsion: position(&format!("PULSION_{}", section), &c.pulsion, langue),
+        action: position(&format!("ACTION_{}", section), &c.action, langue),
+        reaction: position(&format!("REACTION_{}", section), &c.reaction, langue),
+    }
+}
+
+fn etape(position: &str, formule: String, operandes: Vec<usize>) -> Etape {
+    let somme = operandes.iter().sum();
+    Etape { position: position.to_string(), formule, operandes, somme, resultat: reduction_theosophique(somme, false) }
+}
+
+// Étapes des variantes PA et du cycle PPR (vide pour un tableau combiné)
+fn derivation(t: &Tableau, langue: Langue) -> Vec<Etape> {
+    if t.annee == 0 {
+        return vec![];
+    }
+    let (cai, int, cae) = (t.cai.pa.valeur, t.int.pa.valeur, t.cae.pa.valeur);
+    let (coi, coe, ppr) = (t.coi.pa.valeur, t.coe.pa.valeur, t.ppr.pa.valeur);
+    let pulsion = t.cycle_ppr.pulsion.pa.valeur;
+    let action = t.cycle_ppr.action.pa.valeur;
+    vec![
+        etape("CAI", message(langue, "derivation-jour", &[]), vec![t.jour]),
+        etape("INT", message(langue, "derivation-mois", &[]), vec![t.mois]),
+        etape("CAE", message(langue, "derivation-annee", &[]), vec![t.annee]),
+        etape("COI", "CAI + INT".to_string(), vec![cai, int]),
+        etape("COE", "CAE + INT".to_string(), vec![cae, int]),
+        etape("NEM", "CAI + CAE".to_string(), vec![cai, cae]),
+        etape("PEX", "COI + COE".to_string(), vec![coi, coe]),
+        etape("PPR", "CAI + INT + CAE".to_string(), vec![cai, int, cae]),
+        etape("PULSION_PPR", message(langue, "derivation-age", &[]), vec![t.age, 1]),
+        etape("ACTION_PPR", "PULSION + PPR".to_string(), vec![pulsion, ppr]),
+        etape("REACTION_PPR", "ACTION + PPR".to_string(), vec![action, ppr]),
+    ]
+}
+
+impl ContexteRapport {
+    // Analyse avec les regroupements par défaut
+    pub fn new(tableau: &Tableau, langue: Langue) -> ContexteRapport {
+        ContexteRapport::avec_groupements(tableau, &Groupement::par_defaut_langue(langue), langue)
+    }
+
+    pub fn avec_groupements(tableau: &Tableau, groupements: &[Groupement], langue: Langue) -> ContexteRapport {
+        let t = tableau;
+        let libelles = LIBELLES
+            .iter()
+            .map(|l| (l.to_string(), message(langue, &format!("libelle-{}", l), &[])))
+            .collect();
+        ContexteRapport {
+            langue: langue.code().to_string(),
+            naissance: (t.annee > 0).then(|| format!("{:02}.{:02}.{:04}", t.jour, t.mois, t.annee)),
+            age: t.age,
+            image: None,
+            svg: None,
+            positions: t.positions().iter().take(8).map(|(code, p)| position(code, p, langue)).collect(),
+            cycle: vec![
+                cycle("PPR", &t.cycle_ppr, langue),
+                cycle("NEM", &t.cycle_nem, langue),
+                cycle("PEX", &t.cycle_pex, langue),
+            ],
+            derivation: derivation(t, langue),
+            analyse: analyser_langue(t, groupements, langue),
+            lecture: None,
+            libelles,
+        }
+    }
+
+    pub fn lecture(mut self, lecture: Lecture) -> ContexteRapport {
+        self.lecture = Some(lecture);
+        self
+    }
+
+    #[cfg(feature = "image")]
+    pub fn image_png(mut self, image: &image::RgbaImage) -> Result<ContexteRapport, Box<dyn Error>> {
+        self.image = Some(crate::export::data_uri_png(&crate::template::encoder_png(image)?));
+        Ok(self)
+    }
+
+    #[cfg(feature = "image")]
+    pub fn image_svg(mut self, image: &image::RgbaImage) -> Result<ContexteRapport, Box<dyn Error>> {
+        self.svg = Some(String::from_utf8(crate::export::encoder_svg_matriciel(image)?)?);
+        Ok(self)
+    }
+}
+
+// modele: None pour le modèle par défaut du format
+pub fn rendre_rapport(contexte: &ContexteRapport, format: FormatRapport, modele: Option<&str>) -> Result<String, Erreur> {
+    let mut env = minijinja::Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+    env.add_template(format.nom_modele(), modele.unwrap_or(format.modele_par_defaut()))
+        .map_err(|e| Erreur::new("erreur-modele-rapport", &[]).avec_cause(e))?;
+    env.get_template(format.nom_modele())
+        .and_then(|t| t.render(contexte))
+        .map_err(|e| Erreur::new("erreur-rendu-rapport", &[]).avec_cause(e))
+}
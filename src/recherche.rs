@@ -0,0 +1,146 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::definitions::avec_plan_integre;
+use crate::langue::Erreur;
+use crate::tableau::NOMS_POSITIONS;
+use crate::{Date, Tableau};
+
+const VARIANTES: [&str; 4] = ["PA", "PB", "SA", "SB"];
+
+/*********************************************
+ * Condition sur une variante               *
+ * les comparaisons de valeur exigent que   *
+ * la variante soit visible                 *
+ *********************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Egal(usize),
+    Different(usize),
+    Parmi(Vec<usize>),
+    Present,
+    Absent,
+}
+
+/*********************************************
+ * Contrainte: POSITION.VARIANTE condition  *
+ * PPR.PA = 19                              *
+ * NEM.PA != 7                              *
+ * NEM.PA in {7, 16}                        *
+ * PEX.SB present / PEX.SB absent           *
+ *********************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contrainte {
+    // Indice dans Tableau::positions
+    pub position: usize,
+    // Indice dans Position::variantes
+    pub variante: usize,
+    pub condition: Condition,
+}
+
+impl Contrainte {
+    pub fn verifie(&self, tableau: &Tableau) -> bool {
+        self.verifie_arcane(tableau.positions()[self.position].1.variantes()[self.variante].1.visible())
+    }
+
+    // arcane: valeur de la variante si elle est visible
+    fn verifie_arcane(&self, arcane: Option<usize>) -> bool {
+        match (&self.condition, arcane) {
+            (Condition::Present, a) => a.is_some(),
+            (Condition::Absent, a) => a.is_none(),
+            (_, None) => false,
+            (Condition::Egal(x), Some(a)) => a == *x,
+            (Condition::Different(x), Some(a)) => a != *x,
+            (Condition::Parmi(v), Some(a)) => v.contains(&a),
+        }
+    }
+}
+
+fn arcane(s: &str) -> Result<usize, Erreur> {
+    match s.trim().parse::<usize>() {
+        Ok(x) if (1..=22).contains(&x) => Ok(x),
+        _ => Err(Erreur::new("erreur-arcane", &[("texte", s.trim().to_string())])),
+    }
+}
+
+impl FromStr for Contrainte {
+    type Err = Erreur;
+
+    fn from_str(s: &str) -> Result<Contrainte, Erreur> {
+        let s = s.trim();
+        let fin_cible = s.find(|c: char| c.is_whitespace() || c == '=' || c == '!').unwrap_or(s.len());
+        let (cible, reste) = s.split_at(fin_cible);
+        let (position, variante) = cible
+            .split_once('.')
+            .ok_or_else(|| Erreur::new("erreur-contrainte", &[("texte", s.to_string())]))?;
+        let position = NOMS_POSITIONS
+            .iter()
+            .position(|p| p.eq_ignore_ascii_case(position))
+            .ok_or_else(|| Erreur::new("erreur-position", &[("position", position.to_string()), ("positions", NOMS_POSITIONS.join(", "))]))?;
+        let variante = VARIANTES
+            .iter()
+            .position(|v| v.eq_ignore_ascii_case(variante))
+            .ok_or_else(|| Erreur::new("erreur-variante", &[("variante", variante.to_string())]))?;
+        let reste = reste.trim();
+        let condition = if let Some(x) = reste.strip_prefix("!=") {
+            Condition::Different(arcane(x)?)
+        } else if let Some(x) = reste.strip_prefix('=') {
+            Condition::Egal(arcane(x.trim_start_matches('='))?)
+        } else if reste.eq_ignore_ascii_case("present") || reste.eq_ignore_ascii_case("présent") {
+            Condition::Present
+        } else if reste.eq_ignore_ascii_case("absent") {
+            Condition::Absent
+        } else if reste.len() > 2 && reste[..2].eq_ignore_ascii_case("in") {
+            let liste = reste[2..].trim();
+            let liste = liste
+                .strip_prefix('{')
+                .and_then(|l| l.strip_suffix('}'))
+                .ok_or_else(|| Erreur::new("erreur-liste", &[("texte", liste.to_string())]))?;
+            Condition::Parmi(liste.split(',').map(arcane).collect::<Result<Vec<_>, _>>()?)
+        } else {
+            return Err(Erreur::new("erreur-condition", &[("texte", reste.to_string())]));
+        };
+        Ok(Contrainte { position, variante, condition })
+    }
+}
+
+impl fmt::Display for Contrainte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{} ", NOMS_POSITIONS[self.position], VARIANTES[self.variante])?;
+        match &self.condition {
+            Condition::Egal(x) => write!(f, "= {}", x),
+            Condition::Different(x) => write!(f, "!= {}", x),
+            Condition::Parmi(v) => write!(f, "in {{{}}}", v.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(", ")),
+            Condition::Present => write!(f, "present"),
+            Condition::Absent => write!(f, "absent"),
+        }
+    }
+}
+
+/****************************************************
+ * Dates de naissance (bornes incluses) dont le     *
+ * tableau respecte toutes les contraintes          *
+ * age: âge utilisé pour les positions du cycle     *
+ * Seules les positions contraintes sont calculées, *
+ * les moins coûteuses d'abord: une date écartée    *
+ * l'est au plus tôt                                *
+ ****************************************************/
+pub fn rechercher(contraintes: &[Contrainte], debut: &Date, fin: &Date, age: usize) -> Vec<Date> {
+    avec_plan_integre(|plan| {
+        let table = &plan.definitions().positions;
+        let mut ordre: Vec<(usize, &Contrainte)> = contraintes
+            .iter()
+            .map(|c| (table.iter().position(|d| d.nom == NOMS_POSITIONS[c.position]).expect("position de la table intégrée"), c))
+            .collect();
+        ordre.sort_by_key(|(i, _)| plan.cout(*i));
+        let mut dates = vec![];
+        let mut date = *debut;
+        while date <= *fin {
+            let mut partielle = plan.partielle(date.jour, date.mois, date.annee, age);
+            if ordre.iter().all(|(i, c)| c.verifie_arcane(partielle.position(*i).variantes()[c.variante].1.visible())) {
+                dates.push(date);
+            }
+            date = date.lendemain();
+        }
+        dates
+    })
+}
@@ -0,0 +1,233 @@
+use crate::calque::{LayerId, VarianteCalque};
+use crate::langue::Erreur;
+use crate::{calcul, DefinitionPosition, Definitions, ErreurDefinition, Evaluation, Position, Tableau, VisibilityPolicy};
+
+/*****************************************************
+ * Couches d'un tableau dessiné, la première dessous *
+ * Ordre habituel: cadres B, valeurs B, cadres A,    *
+ * valeurs A                                         *
+ *****************************************************/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Couche {
+    CadresB,
+    ValeursB,
+    CadresA,
+    ValeursA,
+}
+
+impl Couche {
+    pub const TOUTES: [Couche; 4] = [Couche::CadresB, Couche::ValeursB, Couche::CadresA, Couche::ValeursA];
+}
+
+/*****************************************************
+ * Mise en valeur du cycle de l'année en cours       *
+ * (pulsion, action, réaction de l'âge + 1)          *
+ * Teinte: couleur mêlée aux calques du cycle        *
+ * (force de 0 à 1)                                  *
+ * Halo: lueur autour du cycle, rayon en pixels      *
+ * Attenuation: tout le reste estompé (opacité de 0  *
+ * à 1)                                              *
+ *****************************************************/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effet {
+    Teinte { couleur: [u8; 3], force: f32 },
+    Halo { couleur: [u8; 3], rayon: u32 },
+    Attenuation(f32),
+}
+
+// voisins: opacité des cycles de l'année précédente et suivante, absent = non dessinés
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accent {
+    pub effet: Effet,
+    pub voisins: Option<f32>,
+}
+
+/*****************************************************
+ * Options de rendu                                  *
+ * visibilite: variantes dessinées                   *
+ * sections: sections dessinées (PPR = PPR et son    *
+ * cycle), dans l'ordre d'empilement, vide = toutes  *
+ * dans l'ordre de la table                          *
+ * couches: couches dessinées, dans l'ordre          *
+ * d'empilement                                      *
+ * par_section: chaque section entière (toutes ses   *
+ * couches) avant la suivante, plutôt que couche     *
+ * par couche sur toutes les sections                *
+ * accent: mise en valeur du cycle en cours          *
+ *****************************************************/
+#[derive(Debug, Clone)]
+pub struct OptionsRendu {
+    pub visibilite: VisibilityPolicy,
+    pub sections: Vec<String>,
+    pub couches: Vec<Couche>,
+    pub par_section: bool,
+    pub accent: Option<Accent>,
+}
+
+impl Default for OptionsRendu {
+    fn default() -> OptionsRendu {
+        OptionsRendu { visibilite: VisibilityPolicy::default(), sections: vec![], couches: Couche::TOUTES.to_vec(), par_section: false, accent: None }
+    }
+}
+
+impl OptionsRendu {
+    // Sections demandées absentes des positions dessinées de la table
+    pub fn verifier(&self, definitions: &Definitions) -> Result<(), Erreur> {
+        let dessinees = sections_dessinees(definitions.positions.iter());
+        match self.sections.iter().find(|s| !dessinees.contains(&s.as_str())) {
+            Some(s) => Err(Erreur::new("erreur-section", &[("section", s.clone()), ("sections", dessinees.join(", "))])),
+            None => Ok(()),
+        }
+    }
+
+    pub fn calques_tableau(&self, tableau: &Tableau) -> Vec<LayerId> {
+        let tableau = self.visibilite.tableau(tableau);
+        let definitions = Definitions::integree();
+        self.calques(definitions.positions.iter().filter_map(|d| tableau.position(&d.nom).map(|p| (d, p))))
+    }
+
+    pub fn calques_evaluation(&self, evaluation: &Evaluation) -> Vec<LayerId> {
+        let evaluation = self.visibilite.evaluation(evaluation.clone());
+        self.calques(evaluation.positions.iter().map(|(d, p)| (*d, *p)))
+    }
+
+    /*********************************************
+     * Calques d'une section à la fois, pour une *
+     * image par section                         *
+     *********************************************/
+    pub fn calques_par_section_tableau(&self, tableau: &Tableau) -> Vec<(String, Vec<LayerId>)> {
+        let definitions = Definitions::integree();
+        self.par_section(definitions.positions.iter(), |options| options.calques_tableau(tableau))
+    }
+
+    pub fn calques_par_section_evaluation(&self, evaluation: &Evaluation) -> Vec<(String, Vec<LayerId>)> {
+        self.par_section(evaluation.positions.iter().map(|(d, _)| *d), |options| options.calques_evaluation(evaluation))
+    }
+
+    /*********************************************
+     * Calques du cycle des années précédente et *
+     * suivante, si l'accent les demande (pas    *
+     * pour un tableau sans date de naissance)   *
+     *********************************************/
+    pub fn calques_voisins_tableau(&self, tableau: &Tableau) -> Vec<LayerId> {
+        let definitions = Definitions::integree();
+        let cycle = prefixes_cycle(definitions.positions.iter());
+        self.ages_voisins(tableau.annee, tableau.age)
+            .flat_map(|age| self.calques_tableau(&calcul(tableau.jour, tableau.mois, tableau.annee, age)))
+            .filter(|c| cycle.contains(&c.section.as_str()))
+            .collect()
+    }
+
+    pub fn calques_voisins_evaluation(&self, evaluation: &Evaluation) -> Result<Vec<LayerId>, ErreurDefinition> {
+        let definitions = Definitions { positions: evaluation.positions.iter().map(|(d, _)| (*d).clone()).collect() };
+        let cycle = prefixes_cycle(definitions.positions.iter());
+        let plan = definitions.compiler()?;
+        let mut calques = vec![];
+        for age in self.ages_voisins(evaluation.annee, evaluation.age) {
+            let voisine = plan.evaluer(evaluation.jour, evaluation.mois, evaluation.annee, age);
+            calques.extend(self.calques_evaluation(&voisine).into_iter().filter(|c| cycle.contains(&c.section.as_str())));
+        }
+        Ok(calques)
+    }
+
+    fn ages_voisins(&self, annee: usize, age: usize) -> impl Iterator<Item = usize> {
+        let voisins = annee > 0 && self.accent.is_some_and(|a| a.voisins.is_some());
+        [age.checked_sub(1), Some(age + 1)].into_iter().flatten().filter(move |_| voisins)
+    }
+
+    // Sections demandées, sinon toutes celles de la table
+    fn par_section<'a>(&self, positions: impl Iterator<Item = &'a DefinitionPosition>, calques: impl Fn(&OptionsRendu) -> Vec<LayerId>) -> Vec<(String, Vec<LayerId>)> {
+        let sections = if self.sections.is_empty() {
+            sections_dessinees(positions).into_iter().map(|s| s.to_string()).collect()
+        } else {
+            self.sections.clone()
+        };
+        sections
+            .into_iter()
+            .map(|s| {
+                let c = calques(&self.pour_section(&s));
+                (s, c)
+            })
+            .collect()
+    }
+
+    // Mêmes options, une seule section
+    pub fn pour_section(&self, section: &str) -> OptionsRendu {
+        OptionsRendu { sections: vec![section.to_string()], ..self.clone() }
+    }
+
+    /*********************************************
+     * Préfixe de chaque position dessinée donné *
+     * par la table, cadre déduit de la valeur   *
+     *********************************************/
+    fn calques<'a>(&self, positions: impl Iterator<Item = (&'a DefinitionPosition, Position)>) -> Vec<LayerId> {
+        let positions: Vec<(&DefinitionPosition, Position)> = positions.filter(|(d, _)| d.calque.is_some()).collect();
+        let sections: Vec<&str> = if self.sections.is_empty() {
+            sections_dessinees(positions.iter().map(|(d, _)| *d))
+        } else {
+            self.sections.iter().map(|s| s.as_str()).collect()
+        };
+        // Couches de chaque section, dans l'ordre des sections
+        let couches: Vec<[Vec<LayerId>; 4]> = sections
+            .iter()
+            .map(|s| couches(positions.iter().filter(|(d, _)| d.section() == *s).copied()))
+            .collect();
+        self.empiler(couches)
+    }
+
+    // Couches demandées de chaque section, section par section ou couche par couche
+    pub(crate) fn empiler(&self, mut couches: Vec<[Vec<LayerId>; 4]>) -> Vec<LayerId> {
+        let mut calques = vec![];
+        if self.par_section {
+            for c in couches.iter_mut() {
+                for couche in self.couches.iter() {
+                    calques.append(&mut c[*couche as usize]);
+                }
+            }
+        } else {
+            for couche in self.couches.iter() {
+                for c in couches.iter_mut() {
+                    calques.append(&mut c[*couche as usize]);
+                }
+            }
+        }
+        calques
+    }
+}
+
+// Cadres B, valeurs B, cadres A, valeurs A des positions, dans l'ordre de la table
+fn couches<'a>(positions: impl Iterator<Item = (&'a DefinitionPosition, Position)>) -> [Vec<LayerId>; 4] {
+    let mut couches: [Vec<LayerId>; 4] = Default::default();
+    for (d, position) in positions {
+        let Some(prefixe) = d.calque.as_deref() else { continue };
+        for (variante, (_, arcane)) in VarianteCalque::TOUTES.into_iter().zip(position.variantes()) {
+            if let Some(x) = arcane.visible() {
+                let (cadres, valeurs) = if variante.est_b() { (Couche::CadresB, Couche::ValeursB) } else { (Couche::CadresA, Couche::ValeursA) };
+                let valeur = LayerId::valeur(prefixe, variante, x);
+                if d.cadre {
+                    couches[cadres as usize].extend(valeur.cadre());
+                }
+                couches[valeurs as usize].push(valeur);
+            }
+        }
+    }
+    couches
+}
+
+// Préfixes des calques du cycle (PULSION_PPR, ACTION_PPR...: PPP, APP...)
+pub fn prefixes_cycle<'a>(positions: impl Iterator<Item = &'a DefinitionPosition>) -> Vec<&'a str> {
+    positions.filter(|d| d.section() != d.nom).filter_map(|d| d.calque.as_deref()).collect()
+}
+
+// Sections des positions dessinées, dans l'ordre de la table
+fn sections_dessinees<'a>(positions: impl Iterator<Item = &'a DefinitionPosition>) -> Vec<&'a str> {
+    let mut sections: Vec<&str> = vec![];
+    for d in positions.filter(|d| d.calque.is_some()) {
+        if !sections.contains(&d.section()) {
+            sections.push(d.section());
+        }
+    }
+    sections
+}
@@ -0,0 +1,197 @@
+use std::error::Error;
+use std::sync::Arc;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use crate::analyse::{analyser_langue, Analyse, Groupement};
+use crate::date::{plage_ages, verifier_age, Date, ErreurDate};
+use crate::export::{encoder_pdf_avec_texte, encoder_svg_matriciel};
+use crate::langue::{message, message_erreur, Erreur, Langue, Localise};
+use crate::template::{encoder_png, Template};
+use crate::{calcul, chronologie, Tableau};
+
+const OPENAPI: &str = include_str!("openapi.json");
+
+/*********************************************
+ * Service HTTP: calcul et rendu des         *
+ * tableaux, le template PSD reste en        *
+ * mémoire (sans template, /render répond    *
+ * 503). Servi par le binaire server         *
+ *********************************************/
+pub fn application(template: Option<Template>) -> Router {
+    Router::new()
+        .route("/health", get(sante))
+        .route("/chart", post(tableau))
+        .route("/render", post(rendu))
+        .route("/timeline", get(timeline))
+        .route("/openapi.json", get(openapi))
+        .with_state(Arc::new(Etat { template }))
+}
+
+struct Etat {
+    template: Option<Template>,
+}
+
+/****************************************
+ * Erreur renvoyée en JSON, dans la     *
+ * langue de la requête                 *
+ * {"erreur": "..."}                    *
+ ****************************************/
+struct ErreurApi(StatusCode, String);
+
+impl IntoResponse for ErreurApi {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "erreur": self.1 }))).into_response()
+    }
+}
+
+// Lecture avant la naissance: 422, autre date, âge ou valeur invalide: 400
+fn requete_invalide(e: impl Into<Box<dyn Error>>, langue: Langue) -> ErreurApi {
+    let e = e.into();
+    let statut = match e.downcast_ref::<ErreurDate>() {
+        Some(ErreurDate::LectureAvantNaissance { .. }) => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    ErreurApi(statut, message_erreur(e.as_ref(), langue))
+}
+
+fn langue(langue: Option<&str>) -> Result<Langue, ErreurApi> {
+    match langue {
+        Some(l) => l.parse().map_err(|e| requete_invalide(e, Langue::default())),
+        None => Ok(Langue::default()),
+    }
+}
+
+#[derive(Deserialize)]
+struct RequeteTableau {
+    naissance: String,
+    lecture: Option<String>,
+    age: Option<usize>,
+    // Regroupements en plus des groupements par défaut
+    #[serde(default)]
+    groupements: Vec<String>,
+    // fr (défaut) ou en: noms des arcanes, analyse et PDF
+    langue: Option<String>,
+}
+
+impl RequeteTableau {
+    // Âge borné comme dans la ligne de commande (AGE_MAX)
+    fn valider(&self) -> Result<(Date, usize), ErreurApi> {
+        let langue = self.langue()?;
+        let naissance: Date = self.naissance.parse().map_err(|e| requete_invalide(e, langue))?;
+        let age = match (self.age, self.lecture.as_deref()) {
+            (Some(_), Some(_)) => Err(Erreur::new("erreur-age-ou-lecture", &[]).into()),
+            (Some(age), None) => verifier_age(age).map_err(Box::from),
+            (None, lecture) => {
+                let lecture = match lecture {
+                    Some(s) => s.parse::<Date>().map_err(|e| requete_invalide(e, langue))?,
+                    None => Date::aujourd_hui(),
+                };
+                naissance.age_le(&lecture).map_err(Box::from)
+            },
+        };
+        let age = age.map_err(|e: Box<dyn Error>| requete_invalide(e, langue))?;
+        Ok((naissance, age))
+    }
+
+    fn langue(&self) -> Result<Langue, ErreurApi> {
+        langue(self.langue.as_deref())
+    }
+
+    fn analyse(&self, tableau: &Tableau) -> Result<Analyse, ErreurApi> {
+        let langue = self.langue()?;
+        let mut groupements = Groupement::par_defaut_langue(langue);
+        for g in self.groupements.iter() {
+            groupements.push(g.parse().map_err(|e| requete_invalide(e, langue))?);
+        }
+        Ok(analyser_langue(tableau, &groupements, langue))
+    }
+}
+
+#[derive(Serialize)]
+struct ReponseTableau {
+    #[serde(flatten)]
+    tableau: Tableau,
+    analyse: Analyse,
+}
+
+#[derive(Deserialize)]
+struct RequeteChronologie {
+    naissance: String,
+    de: usize,
+    a: usize,
+    langue: Option<String>,
+}
+
+async fn sante(State(etat): State<Arc<Etat>>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "statut": "ok", "template": etat.template.is_some() }))
+}
+
+async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI)
+}
+
+async fn tableau(Json(requete): Json<RequeteTableau>) -> Result<Json<ReponseTableau>, ErreurApi> {
+    let (n, age) = requete.valider()?;
+    let tableau = calcul(n.jour, n.mois, n.annee, age);
+    let analyse = requete.analyse(&tableau)?;
+    Ok(Json(ReponseTableau { tableau, analyse }))
+}
+
+async fn timeline(Query(requete): Query<RequeteChronologie>) -> Result<Json<Vec<Tableau>>, ErreurApi> {
+    let langue = langue(requete.langue.as_deref())?;
+    let n: Date = requete.naissance.parse().map_err(|e| requete_invalide(e, langue))?;
+    // Âges bornés par AGE_MAX: la réponse reste raisonnable
+    let ages = plage_ages(requete.de, requete.a).map_err(|e| requete_invalide(e, langue))?;
+    Ok(Json(chronologie(n.jour, n.mois, n.annee, ages)))
+}
+
+/*********************************************
+ * Format selon l'en-tête Accept:            *
+ * image/png (défaut), image/svg+xml (PNG    *
+ * enveloppé, pas de tracé vectoriel),       *
+ * application/pdf (avec le tableau et son   *
+ * analyse en texte), None sinon (406)       *
+ *********************************************/
+pub fn format_accept(accept: &str) -> Option<&'static str> {
+    if accept.contains("image/svg+xml") {
+        Some("image/svg+xml")
+    } else if accept.contains("application/pdf") {
+        Some("application/pdf")
+    } else if accept.contains("image/png") || accept.contains("image/*") || accept.contains("*/*") {
+        Some("image/png")
+    } else {
+        None
+    }
+}
+
+async fn rendu(State(etat): State<Arc<Etat>>, entetes: HeaderMap, Json(requete): Json<RequeteTableau>) -> Result<Response, ErreurApi> {
+    let (n, age) = requete.valider()?;
+    let tableau = calcul(n.jour, n.mois, n.annee, age);
+    let langue = requete.langue()?;
+    let texte = format!("{}{}", tableau.dans(langue), requete.analyse(&tableau)?.dans(langue));
+    let accept = entetes.get(header::ACCEPT).and_then(|v| v.to_str().ok()).unwrap_or("image/png");
+    let format = format_accept(accept)
+        .ok_or_else(|| ErreurApi(StatusCode::NOT_ACCEPTABLE, message(langue, "erreur-format-accept", &[("format", accept.to_string())])))?;
+    let sans_template = move || message(langue, "erreur-template-absent", &[]);
+    if etat.template.is_none() {
+        return Err(ErreurApi(StatusCode::SERVICE_UNAVAILABLE, sans_template()));
+    }
+    let octets = tokio::task::spawn_blocking(move || -> Result<Vec<u8>, String> {
+        let template = etat.template.as_ref().ok_or_else(sans_template)?;
+        let image = template.image_tableau(&tableau).map_err(|e| message_erreur(e.as_ref(), langue))?;
+        let octets = match format {
+            "image/svg+xml" => encoder_svg_matriciel(&image),
+            "application/pdf" => encoder_pdf_avec_texte(&image, &texte),
+            _ => encoder_png(&image),
+        };
+        octets.map_err(|e| message_erreur(e.as_ref(), langue))
+    })
+    .await
+    .map_err(|e| ErreurApi(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| ErreurApi(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(([(header::CONTENT_TYPE, format)], octets).into_response())
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Write;
+use crate::langue::Erreur;
+use crate::tableau::NOMS_POSITIONS;
+use crate::{calcul, Date, Tableau};
+
+const VARIANTES: [&str; 4] = ["PA", "PB", "SA", "SB"];
+
+/*****************************************************
+ * Pondération par natalité, CSV sans guillemets:   *
+ * annee,poids | annee,mois,poids |                  *
+ * annee,mois,jour,poids                             *
+ * La ligne la plus précise l'emporte; une date non  *
+ * couverte par la table a un poids nul              *
+ *****************************************************/
+#[derive(Debug, Clone, Default)]
+pub struct Ponderation {
+    par_annee: HashMap<usize, f64>,
+    par_mois: HashMap<(usize, usize), f64>,
+    par_jour: HashMap<(usize, usize, usize), f64>,
+}
+
+impl Ponderation {
+    pub fn depuis_csv(texte: &str) -> Result<Ponderation, Box<dyn Error>> {
+        let mut p = Ponderation::default();
+        for (i, ligne) in texte.lines().enumerate() {
+            let champs: Vec<&str> = ligne.split([',', ';']).map(|c| c.trim()).collect();
+            if ligne.trim().is_empty() {
+                continue;
+            }
+            let entiers: Result<Vec<usize>, _> = champs[..champs.len() - 1].iter().map(|c| c.parse::<usize>()).collect();
+            let poids = champs[champs.len() - 1].parse::<f64>();
+            let (entiers, poids) = match (entiers, poids) {
+                (Ok(e), Ok(p)) if p >= 0.0 => (e, p),
+                // En-tête
+                _ if i == 0 => continue,
+                _ => return Err(Erreur::new("erreur-ponderation-ligne", &[("ligne", (i + 1).to_string()), ("texte", ligne.to_string())]).into()),
+            };
+            match entiers[..] {
+                [annee] => { p.par_annee.insert(annee, poids); },
+                [annee, mois] => { p.par_mois.insert((annee, mois), poids); },
+                [annee, mois, jour] => { p.par_jour.insert((annee, mois, jour), poids); },
+                _ => return Err(Erreur::new("erreur-ponderation-colonnes", &[("ligne", (i + 1).to_string())]).into()),
+            }
+        }
+        Ok(p)
+    }
+
+    // Le poids d'un mois ou d'une année est réparti sur ses jours
+    pub fn poids(&self, date: &Date) -> f64 {
+        if let Some(p) = self.par_jour.get(&(date.annee, date.mois, date.jour)) {
+            return *p;
+        }
+        let jours_mois = crate::date::jours_dans_mois(date.mois, date.annee) as f64;
+        if let Some(p) = self.par_mois.get(&(date.annee, date.mois)) {
+            return *p / jours_mois;
+        }
+        let jours_annee = if crate::date::est_bissextile(date.annee) { 366.0 } else { 365.0 };
+        self.par_annee.get(&date.annee).map(|p| *p / jours_annee).unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatVariante {
+    pub variante: String,
+    // Part des naissances où la variante est visible
+    pub presence: f64,
+    // Part des naissances par arcane (indice 0 = arcane 1)
+    pub frequences: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatPosition {
+    pub position: String,
+    pub variantes: Vec<StatVariante>,
+}
+
+/*************************************************
+ * Distribution des arcanes sur une population   *
+ * de dates de naissance, pour les 17 positions  *
+ * (celles du cycle à l'âge donné)               *
+ *************************************************/
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Statistiques {
+    pub debut: Date,
+    pub fin: Date,
+    pub age: usize,
+    pub poids_total: f64,
+    pub positions: Vec<StatPosition>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    configurations: HashMap<Configuration, f64>,
+}
+
+// Variantes visibles des 17 positions
+type Configuration = [Option<u8>; NOMS_POSITIONS.len() * 4];
+
+fn configuration(tableau: &Tableau) -> Configuration {
+    let positions = tableau.positions();
+    std::array::from_fn(|i| positions[i / 4].1.variantes()[i % 4].1.visible().map(|x| x as u8))
+}
+
+pub fn statistiques(debut: &Date, fin: &Date, age: usize, ponderation: Option<&Ponderation>) -> Statistiques {
+    let mut poids_total = 0.0;
+    let mut presence = [[0.0f64; 4]; NOMS_POSITIONS.len()];
+    let mut frequences = [[[0.0f64; 22]; 4]; NOMS_POSITIONS.len()];
+    let mut configurations: HashMap<Configuration, f64> = HashMap::new();
+    let mut date = *debut;
+    while date <= *fin {
+        let poids = ponderation.map(|p| p.poids(&date)).unwrap_or(1.0);
+        if poids > 0.0 {
+            let tableau = calcul(date.jour, date.mois, date.annee, age);
+            poids_total += poids;
+            for (i, (_, position)) in tableau.positions().iter().enumerate() {
+                for (j, (_, arcane)) in position.variantes().iter().enumerate() {
+                    if let Some(x) = arcane.visible() {
+                        presence[i][j] += poids;
+                        frequences[i][j][x - 1] += poids;
+                    }
+                }
+            }
+            *configurations.entry(configuration(&tableau)).or_insert(0.0) += poids;
+        }
+        date = date.lendemain();
+    }
+    let part = |x: f64| if poids_total > 0.0 { x / poids_total } else { 0.0 };
+    let positions = NOMS_POSITIONS
+        .iter()
+        .enumerate()
+        .map(|(i, nom)| StatPosition {
+            position: nom.to_string(),
+            variantes: (0..4)
+                .map(|j| StatVariante {
+                    variante: VARIANTES[j].to_string(),
+                    presence: part(presence[i][j]),
+                    frequences: frequences[i][j].iter().map(|x| part(*x)).collect(),
+                })
+                .collect(),
+        })
+        .collect();
+    Statistiques { debut: *debut, fin: *fin, age, poids_total, positions, configurations }
+}
+
+impl Statistiques {
+    /*********************************************************
+     * Rareté d'un tableau, en percentile (0 à 100):         *
+     * part de la population dont la configuration des 17    *
+     * positions est plus fréquente que la sienne; tableau   *
+     * calculé à l'âge des statistiques                      *
+     *********************************************************/
+    pub fn rarete(&self, tableau: &Tableau) -> f64 {
+        if self.poids_total <= 0.0 {
+            return 0.0;
+        }
+        let frequence = self.configurations.get(&configuration(tableau)).copied().unwrap_or(0.0);
+        // fold plutôt que sum: la somme vide de f64 vaut -0.0
+        let plus_frequentes = self.configurations.values().filter(|p| **p > frequence).fold(0.0, |total, p| total + p);
+        100.0 * plus_frequentes / self.poids_total
+    }
+
+    // position,variante,arcane,frequence (une ligne par arcane)
+    pub fn csv(&self) -> String {
+        let mut csv = String::from("position,variante,presence,arcane,frequence\n");
+        for p in self.positions.iter() {
+            for v in p.variantes.iter() {
+                for (i, f) in v.frequences.iter().enumerate() {
+                    let _ = writeln!(csv, "{},{},{:.6},{},{:.6}", p.position, v.variante, v.presence, i + 1, f);
+                }
+            }
+        }
+        csv
+    }
+}
@@ -0,0 +1,522 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::io::Cursor;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+use psd::Psd;
+use image::{imageops, RgbaImage};
+use crate::langue::{message, Langue};
+use crate::nom::{ids_calques_nom, NumerologieNom};
+use crate::rendu::prefixes_cycle;
+use crate::{calcl, calcul, ids_calques_attendus, Definitions, Effet, ErreurDefinition, Evaluation, LayerId, Nommage, OptionsRendu, Tableau, VisibilityPolicy};
+
+/*****************************************************
+ * Erreurs du rendu, traduites par message(langue)   *
+ * Display: français                                 *
+ *****************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurTemplate {
+    Lecture { chemin: String, erreur: String },
+    PsdInvalide { chemin: Option<String>, erreur: String },
+    BufferRgba,
+    TailleCalque { calque: String, obtenu: usize, attendu: usize },
+    Encodage { format: &'static str, erreur: String },
+    AnimationVide,
+    TaillesAnimation,
+    WebpVp8l,
+}
+
+impl ErreurTemplate {
+    pub fn message(&self, langue: Langue) -> String {
+        match self {
+            ErreurTemplate::Lecture { chemin, erreur } => message(langue, "erreur-lecture-psd", &[
+                ("chemin", chemin.clone()),
+                ("erreur", erreur.clone()),
+            ]),
+            ErreurTemplate::PsdInvalide { chemin: Some(chemin), erreur } => message(langue, "erreur-psd-invalide", &[
+                ("chemin", chemin.clone()),
+                ("erreur", erreur.clone()),
+            ]),
+            ErreurTemplate::PsdInvalide { chemin: None, erreur } => message(langue, "erreur-psd-octets", &[
+                ("erreur", erreur.clone()),
+            ]),
+            ErreurTemplate::BufferRgba => message(langue, "erreur-buffer-rgba", &[]),
+            ErreurTemplate::TailleCalque { calque, obtenu, attendu } => message(langue, "erreur-taille-calque", &[
+                ("calque", calque.clone()),
+                ("obtenu", obtenu.to_string()),
+                ("attendu", attendu.to_string()),
+            ]),
+            ErreurTemplate::Encodage { format, erreur } => message(langue, "erreur-encodage", &[
+                ("format", format.to_string()),
+                ("erreur", erreur.clone()),
+            ]),
+            ErreurTemplate::AnimationVide => message(langue, "erreur-animation-vide", &[]),
+            ErreurTemplate::TaillesAnimation => message(langue, "erreur-animation-tailles", &[]),
+            ErreurTemplate::WebpVp8l => message(langue, "erreur-webp-vp8l", &[]),
+        }
+    }
+}
+
+impl fmt::Display for ErreurTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Langue::default()))
+    }
+}
+
+impl Error for ErreurTemplate {}
+
+/*********************************************
+ * Template PSD décodé, gardé en mémoire     *
+ * pour composer plusieurs tableaux sans     *
+ * relire le fichier                         *
+ * nommage: noms des calques du template     *
+ * options: variantes, sections et ordre des *
+ * couches dessinés                          *
+ * fond: image aplatie, décodée à l'ouverture *
+ * calques: décodés au premier usage puis    *
+ * gardés (animation, lot, serveur)          *
+ * psd: absent pour un template d'images     *
+ *********************************************/
+pub struct Template {
+    psd: Option<Psd>,
+    nommage: Nommage,
+    options: OptionsRendu,
+    fond: RgbaImage,
+    calques: Mutex<HashMap<String, Arc<Vec<Calque>>>>,
+}
+
+impl Template {
+    // Pas de système de fichiers en wasm32: voir depuis_octets
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn ouvrir(path_psd: &str) -> Result<Template, Box<dyn Error>> {
+        let psd_bytes = fs::read(path_psd)
+            .map_err(|e| ErreurTemplate::Lecture { chemin: path_psd.to_string(), erreur: e.to_string() })?;
+        let psd = Psd::from_bytes(&psd_bytes)
+            .map_err(|e| ErreurTemplate::PsdInvalide { chemin: Some(path_psd.to_string()), erreur: e.to_string() })?;
+        Template::nouveau(psd)
+    }
+
+    pub fn depuis_octets(psd_bytes: &[u8]) -> Result<Template, Box<dyn Error>> {
+        let psd = Psd::from_bytes(psd_bytes)
+            .map_err(|e| ErreurTemplate::PsdInvalide { chemin: None, erreur: e.to_string() })?;
+        Template::nouveau(psd)
+    }
+
+    fn nouveau(psd: Psd) -> Result<Template, Box<dyn Error>> {
+        let fond = RgbaImage::from_raw(psd.width(), psd.height(), psd.rgba()).ok_or(ErreurTemplate::BufferRgba)?;
+        Ok(Template { psd: Some(psd), nommage: Nommage::default(), options: OptionsRendu::default(), fond, calques: Mutex::default() })
+    }
+
+    /*************************************************
+     * Template sans PSD: fond et calques nommés,    *
+     * chacun à la taille du fond (calques générés,  *
+     * tests de composition)                         *
+     *************************************************/
+    pub fn depuis_images(fond: RgbaImage, calques: Vec<(String, RgbaImage)>) -> Result<Template, ErreurTemplate> {
+        let mut decodes: HashMap<String, Vec<Calque>> = HashMap::new();
+        for (nom, image) in calques {
+            if image.dimensions() != fond.dimensions() {
+                return Err(ErreurTemplate::TailleCalque { calque: nom, obtenu: image.as_raw().len(), attendu: fond.as_raw().len() });
+            }
+            decodes.entry(nom).or_default().extend(Calque::rogner(image.as_raw(), fond.width() as usize));
+        }
+        let calques = decodes.into_iter().map(|(nom, c)| (nom, Arc::new(c))).collect();
+        Ok(Template { psd: None, nommage: Nommage::default(), options: OptionsRendu::default(), fond, calques: Mutex::new(calques) })
+    }
+
+    // Template tiers nommant ses calques autrement
+    pub fn avec_nommage(mut self, nommage: Nommage) -> Template {
+        self.nommage = nommage;
+        self
+    }
+
+    pub fn nommage(&self) -> &Nommage {
+        &self.nommage
+    }
+
+    pub fn avec_visibilite(mut self, visibilite: VisibilityPolicy) -> Template {
+        self.options.visibilite = visibilite;
+        self
+    }
+
+    pub fn avec_options(mut self, options: OptionsRendu) -> Template {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &OptionsRendu {
+        &self.options
+    }
+
+    pub fn largeur(&self) -> u32 {
+        self.fond.width()
+    }
+
+    pub fn hauteur(&self) -> u32 {
+        self.fond.height()
+    }
+
+    // Calques attendus absents du template
+    pub fn calques_manquants(&self) -> Vec<String> {
+        ids_calques_attendus()
+            .iter()
+            .map(|id| self.nommage.nommer(id))
+            .filter(|calque| match &self.psd {
+                Some(psd) => psd.layer_by_name(calque).is_none(),
+                None => self.calques.lock().map_or(true, |c| !c.contains_key(calque)),
+            })
+            .collect()
+    }
+
+    /*************************************
+     * Tableau complet encodé en PNG     *
+     *************************************/
+    pub fn rendre(&self, j: usize, m: usize, a: usize, age: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+        encoder_png(&self.image(j, m, a, age)?)
+    }
+
+    pub fn image(&self, j: usize, m: usize, a: usize, age: usize) -> Result<RgbaImage, Box<dyn Error>> {
+        let definitions = Definitions::integree();
+        let cycle = prefixes_cycle(definitions.positions.iter());
+        self.composer_accent(&calcl(j, m, a, age, &self.options), &cycle, || Ok(self.options.calques_voisins_tableau(&calcul(j, m, a, age))))
+    }
+
+    // Tableau déjà calculé (ex: tableau combiné d'un couple)
+    pub fn image_tableau(&self, tableau: &Tableau) -> Result<RgbaImage, Box<dyn Error>> {
+        self.image_calques_tableau(self.options.calques_tableau(tableau), tableau)
+    }
+
+    // Table des positions chargée, positions ajoutées comprises
+    pub fn image_evaluation(&self, evaluation: &Evaluation) -> Result<RgbaImage, Box<dyn Error>> {
+        self.image_calques_evaluation(self.options.calques_evaluation(evaluation), evaluation)
+    }
+
+    // Tableau et positions du nom (ignorées si le template n'a pas ces calques)
+    pub fn image_avec_nom(&self, tableau: &Tableau, nom: &NumerologieNom) -> Result<RgbaImage, Box<dyn Error>> {
+        let mut c = self.options.calques_tableau(tableau);
+        c.extend(ids_calques_nom(nom, &self.options));
+        self.image_calques_tableau(c, tableau)
+    }
+
+    // Calques d'un tableau, avec la mise en valeur de son cycle
+    pub fn image_calques_tableau(&self, calques: Vec<LayerId>, tableau: &Tableau) -> Result<RgbaImage, Box<dyn Error>> {
+        let definitions = Definitions::integree();
+        let cycle = prefixes_cycle(definitions.positions.iter());
+        self.composer_accent(&calques, &cycle, || Ok(self.options.calques_voisins_tableau(tableau)))
+    }
+
+    pub fn image_calques_evaluation(&self, calques: Vec<LayerId>, evaluation: &Evaluation) -> Result<RgbaImage, Box<dyn Error>> {
+        let cycle = prefixes_cycle(evaluation.positions.iter().map(|(d, _)| *d));
+        self.composer_accent(&calques, &cycle, || self.options.calques_voisins_evaluation(evaluation))
+    }
+
+    // Une image par section (PPR, NEM, PEX...), dans l'ordre des options
+    pub fn images_sections(&self, tableau: &Tableau) -> Result<Vec<(String, RgbaImage)>, Box<dyn Error>> {
+        let definitions = Definitions::integree();
+        let cycle = prefixes_cycle(definitions.positions.iter());
+        self.options
+            .calques_par_section_tableau(tableau)
+            .into_iter()
+            .map(|(section, calques)| {
+                let voisins = || Ok(self.options.pour_section(&section).calques_voisins_tableau(tableau));
+                let image = self.composer_accent(&calques, &cycle, voisins)?;
+                Ok((section, image))
+            })
+            .collect()
+    }
+
+    pub fn images_sections_evaluation(&self, evaluation: &Evaluation) -> Result<Vec<(String, RgbaImage)>, Box<dyn Error>> {
+        let cycle = prefixes_cycle(evaluation.positions.iter().map(|(d, _)| *d));
+        self.options
+            .calques_par_section_evaluation(evaluation)
+            .into_iter()
+            .map(|(section, calques)| {
+                let voisins = || self.options.pour_section(&section).calques_voisins_evaluation(evaluation);
+                let image = self.composer_accent(&calques, &cycle, voisins)?;
+                Ok((section, image))
+            })
+            .collect()
+    }
+
+    /*********************************************
+     * Une image par âge de la plage (animation) *
+     * Le document est décodé une seule fois     *
+     *********************************************/
+    pub fn images_ages(&self, j: usize, m: usize, a: usize, ages: RangeInclusive<usize>) -> Result<Vec<RgbaImage>, Box<dyn Error>> {
+        ages.map(|age| self.image(j, m, a, age)).collect()
+    }
+
+    pub fn images_ages_evaluation(&self, definitions: &Definitions, j: usize, m: usize, a: usize, ages: RangeInclusive<usize>) -> Result<Vec<RgbaImage>, Box<dyn Error>> {
+        let plan = definitions.compiler()?;
+        ages.map(|age| self.image_evaluation(&plan.evaluer(j, m, a, age))).collect()
+    }
+
+    // Calques nommés d'après le nommage du template
+    pub fn composer_calques(&self, calques: &[LayerId]) -> Result<RgbaImage, Box<dyn Error>> {
+        let noms: Vec<String> = calques.iter().map(|id| self.nommage.nommer(id)).collect();
+        self.composer(&noms)
+    }
+
+    /********************************************
+     * Superpose les calques demandés, dans     *
+     * l'ordre, sur l'image aplatie du document *
+     ********************************************/
+    pub fn composer(&self, calques: &[String]) -> Result<RgbaImage, Box<dyn Error>> {
+        let mut image = self.fond.clone();
+        for calque in calques.iter() {
+            self.superposer_calque(&mut image, calque, NORMAL);
+        }
+        Ok(image)
+    }
+
+    /*****************************************************
+     * Composition avec mise en valeur du cycle en cours *
+     * (options.accent), sinon composer_calques          *
+     * cycle: préfixes des calques du cycle              *
+     * voisins: calques des cycles des années voisines,  *
+     * dessinés pâles sous le tableau                    *
+     *****************************************************/
+    fn composer_accent(&self, calques: &[LayerId], cycle: &[&str], voisins: impl FnOnce() -> Result<Vec<LayerId>, ErreurDefinition>) -> Result<RgbaImage, Box<dyn Error>> {
+        let Some(accent) = self.options.accent else {
+            return self.composer_calques(calques);
+        };
+        let mut image = self.fond.clone();
+        let attenuation = match accent.effet {
+            Effet::Attenuation(opacite) => opacite.clamp(0.0, 1.0),
+            _ => 1.0,
+        };
+        if attenuation < 1.0 {
+            estomper(&mut image, attenuation);
+        }
+        if let Some(opacite) = accent.voisins {
+            for id in voisins()?.iter() {
+                self.superposer_calque(&mut image, &self.nommage.nommer(id), Style { opacite: opacite.clamp(0.0, 1.0), teinte: None });
+            }
+        }
+        let est_cycle = |id: &LayerId| cycle.contains(&id.section.as_str());
+        if let Effet::Halo { couleur, rayon } = accent.effet {
+            let (w, h) = (image.width() as usize, image.height() as usize);
+            let mut masque = vec![0.0f32; w * h];
+            for id in calques.iter().filter(|id| est_cycle(id)) {
+                for calque in self.pixels_calque(&self.nommage.nommer(id)).iter() {
+                    for (y, ligne) in calque.pixels.chunks_exact(calque.largeur * 4).enumerate() {
+                        let debut = (calque.y + y) * w + calque.x;
+                        for (m, px) in masque[debut..debut + calque.largeur].iter_mut().zip(ligne.chunks_exact(4)) {
+                            *m = m.max(px[3] as f32 / 255.0);
+                        }
+                    }
+                }
+            }
+            flou(&mut masque, w, h, rayon as usize);
+            let lueur: Vec<u8> = masque
+                .iter()
+                .flat_map(|m| [couleur[0], couleur[1], couleur[2], ((m * 2.0).min(1.0) * 255.0) as u8])
+                .collect();
+            superposer(&mut image, &Calque { x: 0, y: 0, largeur: w, hauteur: h, pixels: lueur }, NORMAL);
+        }
+        for id in calques.iter() {
+            let style = match (est_cycle(id), accent.effet) {
+                (true, Effet::Teinte { couleur, force }) => Style { opacite: 1.0, teinte: Some((couleur, force.clamp(0.0, 1.0))) },
+                (false, Effet::Attenuation(_)) => Style { opacite: attenuation, teinte: None },
+                _ => NORMAL,
+            };
+            self.superposer_calque(&mut image, &self.nommage.nommer(id), style);
+        }
+        Ok(image)
+    }
+
+    // Calques de ce nom, décodés une seule fois (le verrou n'est pas gardé pendant le décodage)
+    fn pixels_calque(&self, nom: &str) -> Arc<Vec<Calque>> {
+        if let Some(calques) = self.calques.lock().ok().and_then(|c| c.get(nom).cloned()) {
+            return calques;
+        }
+        let calques = Arc::new(self.decoder_calque(nom));
+        if let Ok(mut cache) = self.calques.lock() {
+            cache.insert(nom.to_string(), calques.clone());
+        }
+        calques
+    }
+
+    fn decoder_calque(&self, nom: &str) -> Vec<Calque> {
+        let Some(psd) = &self.psd else {
+            return vec![];
+        };
+        let (dw, dh) = (psd.width() as usize, psd.height() as usize);
+        let mut calques = vec![];
+        for layer in psd.layers().iter().filter(|x| x.name() == nom) {
+            let pixels: Vec<u8> = layer.rgba();
+            // Sécurité: on vérifie la cohérence avec la taille du document
+            if pixels.len() != dw * dh * 4 {
+                eprintln!("{}", ErreurTemplate::TailleCalque {
+                    calque: layer.name().to_string(),
+                    obtenu: pixels.len(),
+                    attendu: dw * dh * 4,
+                });
+                continue;
+            }
+            calques.extend(Calque::rogner(&pixels, dw));
+        }
+        calques
+    }
+
+    fn superposer_calque(&self, image: &mut RgbaImage, nom: &str, style: Style) {
+        for calque in self.pixels_calque(nom).iter() {
+            superposer(image, calque, style);
+        }
+    }
+}
+
+/**************************************************
+ * Pixels RGBA d'un calque, réduits au rectangle  *
+ * de ses pixels non transparents (x, y: coin     *
+ * dans le document)                              *
+ **************************************************/
+struct Calque {
+    x: usize,
+    y: usize,
+    largeur: usize,
+    hauteur: usize,
+    pixels: Vec<u8>,
+}
+
+impl Calque {
+    // Buffer à la taille du document, None s'il est entièrement transparent
+    fn rogner(pixels: &[u8], dw: usize) -> Option<Calque> {
+        let mut bornes: Option<(usize, usize, usize, usize)> = None;
+        for (i, px) in pixels.chunks_exact(4).enumerate() {
+            if px[3] != 0 {
+                let (x, y) = (i % dw, i / dw);
+                bornes = Some(match bornes {
+                    None => (x, y, x, y),
+                    // Parcours ligne par ligne: y0 est déjà le plus petit
+                    Some((x0, y0, x1, _)) => (x0.min(x), y0, x1.max(x), y),
+                });
+            }
+        }
+        let (x0, y0, x1, y1) = bornes?;
+        let largeur = x1 - x0 + 1;
+        let pixels = (y0..=y1)
+            .flat_map(|y| pixels[(y * dw + x0) * 4..(y * dw + x0 + largeur) * 4].iter().copied())
+            .collect();
+        Some(Calque { x: x0, y: y0, largeur, hauteur: y1 - y0 + 1, pixels })
+    }
+}
+
+// Opacité du calque et teinte (couleur, force de 0 à 1)
+#[derive(Clone, Copy)]
+struct Style {
+    opacite: f32,
+    teinte: Option<([u8; 3], f32)>,
+}
+
+const NORMAL: Style = Style { opacite: 1.0, teinte: None };
+
+// Superposition du calque à sa place dans le document
+fn superposer(image: &mut RgbaImage, calque: &Calque, style: Style) {
+    let pixels = &calque.pixels;
+    for y in 0..calque.hauteur {
+        for x in 0..calque.largeur {
+            let src_idx = (y * calque.largeur + x) * 4;
+
+            let mut sr = pixels[src_idx] as f32;
+            let mut sg = pixels[src_idx + 1] as f32;
+            let mut sb = pixels[src_idx + 2] as f32;
+            let sa = pixels[src_idx + 3] as f32 / 255.0 * style.opacite;
+
+            if sa == 0.0 {
+                continue;
+            }
+            if let Some((couleur, force)) = style.teinte {
+                sr += (couleur[0] as f32 - sr) * force;
+                sg += (couleur[1] as f32 - sg) * force;
+                sb += (couleur[2] as f32 - sb) * force;
+            }
+            let dst_px = image.get_pixel_mut((calque.x + x) as u32, (calque.y + y) as u32);
+
+            let dr = dst_px[0] as f32;
+            let dg = dst_px[1] as f32;
+            let db = dst_px[2] as f32;
+            let da = dst_px[3] as f32 / 255.0;
+
+            // Alpha-over (SRC over DST)
+            let out_a = sa + da * (1.0 - sa);
+            let (out_r, out_g, out_b) = if out_a > 0.0 {
+                (
+                    (sr * sa + dr * da * (1.0 - sa)) / out_a,
+                    (sg * sa + dg * da * (1.0 - sa)) / out_a,
+                    (sb * sa + db * da * (1.0 - sa)) / out_a,
+                )
+            } else {
+                (0.0, 0.0, 0.0)
+            };
+
+            *dst_px = image::Rgba([
+                out_r.clamp(0.0, 255.0) as u8,
+                out_g.clamp(0.0, 255.0) as u8,
+                out_b.clamp(0.0, 255.0) as u8,
+                (out_a * 255.0).clamp(0.0, 255.0) as u8,
+            ]);
+        }
+    }
+}
+
+// Fond éclairci vers le blanc, opacite = 1: inchangé
+fn estomper(image: &mut RgbaImage, opacite: f32) {
+    for px in image.pixels_mut() {
+        for c in px.0[..3].iter_mut() {
+            *c = (255.0 - (255.0 - *c as f32) * opacite) as u8;
+        }
+    }
+}
+
+// Flou du masque: deux moyennes glissantes horizontales puis verticales
+fn flou(masque: &mut [f32], w: usize, h: usize, rayon: usize) {
+    let mut tmp = vec![0.0f32; masque.len()];
+    for _ in 0..2 {
+        for y in 0..h {
+            moyenne(masque, &mut tmp, y * w, w, 1, rayon);
+        }
+        for x in 0..w {
+            moyenne(&tmp, masque, x, h, w, rayon);
+        }
+    }
+}
+
+// Moyenne sur 2 * rayon + 1 valeurs, n valeurs espacées de pas à partir de debut
+fn moyenne(src: &[f32], dst: &mut [f32], debut: usize, n: usize, pas: usize, rayon: usize) {
+    let v = |i: isize| if i < 0 || i >= n as isize { 0.0 } else { src[debut + i as usize * pas] };
+    let r = rayon as isize;
+    let largeur = (2 * rayon + 1) as f32;
+    let mut somme: f32 = (-r..=r).map(v).sum();
+    for i in 0..n as isize {
+        dst[debut + i as usize * pas] = somme / largeur;
+        somme += v(i + r + 1) - v(i - r);
+    }
+}
+
+pub fn encoder_png(image: &RgbaImage) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut buf = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut buf), image::ImageFormat::Png)
+        .map_err(|e| ErreurTemplate::Encodage { format: "PNG", erreur: e.to_string() })?;
+    Ok(buf)
+}
+
+/*********************************************
+ * Images de même taille en grille, ligne    *
+ * par ligne, sur fond transparent           *
+ *********************************************/
+pub fn planche(images: &[RgbaImage], colonnes: usize) -> RgbaImage {
+    let colonnes = colonnes.clamp(1, images.len().max(1));
+    let lignes = images.len().div_ceil(colonnes);
+    let (w, h) = images.first().map(|i| i.dimensions()).unwrap_or((0, 0));
+    let mut planche = RgbaImage::new(w * colonnes as u32, h * lignes as u32);
+    for (i, image) in images.iter().enumerate() {
+        let (x, y) = ((i % colonnes) as u32 * w, (i / colonnes) as u32 * h);
+        imageops::replace(&mut planche, image, x as i64, y as i64);
+    }
+    planche
+}
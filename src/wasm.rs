@@ -0,0 +1,94 @@
+use std::error::Error;
+use wasm_bindgen::prelude::*;
+use crate::date::{plage_ages, verifier_age};
+use crate::langue::{message_erreur, Erreur};
+use crate::{calcul, chronologie, Date, Langue, Template};
+
+/*******************************************************
+ * Liaisons JavaScript (feature "wasm")                *
+ * Paquet npm:                                         *
+ * wasm-pack build --release --target bundler \        *
+ *     crates/wasm                                     *
+ * Les tableaux sont rendus en objets JS (même forme   *
+ * que le JSON de la commande cycles compute)          *
+ * langue: "fr" (défaut) ou "en", pour les erreurs     *
+ *******************************************************/
+
+fn erreur(e: impl Into<Box<dyn Error>>, langue: Langue) -> JsError {
+    JsError::new(&message_erreur(e.into().as_ref(), langue))
+}
+
+fn langue(langue: Option<String>) -> Result<Langue, JsError> {
+    langue.map_or(Ok(Langue::default()), |l| l.parse().map_err(|e| erreur(e, Langue::default())))
+}
+
+fn date(s: &str, langue: Langue) -> Result<Date, JsError> {
+    s.parse::<Date>().map_err(|e| erreur(e, langue))
+}
+
+fn age(naissance: &Date, lecture: Option<String>, age: Option<usize>, langue: Langue) -> Result<usize, JsError> {
+    if let Some(age) = age {
+        return verifier_age(age).map_err(|e| erreur(e, langue));
+    }
+    let lecture = match lecture {
+        Some(s) => date(&s, langue)?,
+        None => Date::aujourd_hui(),
+    };
+    naissance.age_le(&lecture).map_err(|e| erreur(e, langue))
+}
+
+fn objet_js<T: serde::Serialize>(valeur: &T, langue: Langue) -> Result<JsValue, JsError> {
+    let json = serde_json::to_string(valeur).map_err(|e| erreur(Erreur::new("erreur-json-detail", &[]).avec_cause(e), langue))?;
+    js_sys::JSON::parse(&json).map_err(|_| erreur(Erreur::new("erreur-json", &[]), langue))
+}
+
+// calculer("14.06.1946", "2025-10-18") ou calculer("14.06.1946", undefined, 79, "en")
+#[wasm_bindgen]
+pub fn calculer(naissance: &str, lecture: Option<String>, age_revolu: Option<usize>, langue: Option<String>) -> Result<JsValue, JsError> {
+    let langue = self::langue(langue)?;
+    let n = date(naissance, langue)?;
+    let age = age(&n, lecture, age_revolu, langue)?;
+    objet_js(&calcul(n.jour, n.mois, n.annee, age), langue)
+}
+
+#[wasm_bindgen(js_name = chronologie)]
+pub fn chronologie_js(naissance: &str, de: usize, a: usize, langue: Option<String>) -> Result<JsValue, JsError> {
+    let langue = self::langue(langue)?;
+    let n = date(naissance, langue)?;
+    let ages = plage_ages(de, a).map_err(|e| erreur(e, langue))?;
+    objet_js(&chronologie(n.jour, n.mois, n.annee, ages), langue)
+}
+
+/**************************************************
+ * Template PSD passé en Uint8Array (ex: fetch),  *
+ * décodé une seule fois                          *
+ **************************************************/
+#[wasm_bindgen(js_name = Template)]
+pub struct TemplateJs {
+    template: Template,
+}
+
+#[wasm_bindgen(js_class = Template)]
+impl TemplateJs {
+    #[wasm_bindgen(constructor)]
+    pub fn new(psd: &[u8], langue: Option<String>) -> Result<TemplateJs, JsError> {
+        let langue = self::langue(langue)?;
+        let template = Template::depuis_octets(psd).map_err(|e| erreur(e, langue))?;
+        Ok(TemplateJs { template })
+    }
+
+    #[wasm_bindgen(js_name = calquesManquants)]
+    pub fn calques_manquants(&self) -> Vec<String> {
+        self.template.calques_manquants()
+    }
+
+    // PNG en Uint8Array
+    pub fn rendre(&self, naissance: &str, lecture: Option<String>, age_revolu: Option<usize>, langue: Option<String>) -> Result<Vec<u8>, JsError> {
+        let langue = self::langue(langue)?;
+        let n = date(naissance, langue)?;
+        let age = age(&n, lecture, age_revolu, langue)?;
+        self.template
+            .rendre(n.jour, n.mois, n.annee, age)
+            .map_err(|e| erreur(e, langue))
+    }
+}
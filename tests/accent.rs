@@ -0,0 +1,56 @@
+#![cfg(feature = "image")]
+
+use cycles_numerologie_du_tarot::{calcul, Accent, Effet, LayerId, Nommage, OptionsRendu, Template, VarianteCalque};
+use image::{Rgba, RgbaImage};
+
+// Fond gris 4×1: PPR (hors cycle) noir en x = 0, PPP (pulsion du cycle) rouge en x = 1
+fn template(effet: Option<Effet>) -> (Template, Vec<LayerId>) {
+    let ids = vec![LayerId::valeur("PPR", VarianteCalque::PA, 4), LayerId::valeur("PPP", VarianteCalque::PA, 5)];
+    let calque = |x: u32, couleur: [u8; 4]| {
+        let mut image = RgbaImage::new(4, 1);
+        image.put_pixel(x, 0, Rgba(couleur));
+        image
+    };
+    let nommage = Nommage::default();
+    let calques = vec![(nommage.nommer(&ids[0]), calque(0, [0, 0, 0, 255])), (nommage.nommer(&ids[1]), calque(1, [200, 0, 0, 255]))];
+    let template = Template::depuis_images(RgbaImage::from_pixel(4, 1, Rgba([100, 100, 100, 255])), calques).unwrap();
+    let options = OptionsRendu { accent: effet.map(|effet| Accent { effet, voisins: None }), ..OptionsRendu::default() };
+    (template.avec_options(options), ids)
+}
+
+fn pixels(effet: Option<Effet>) -> Vec<[u8; 4]> {
+    let (template, ids) = template(effet);
+    let image = template.image_calques_tableau(ids, &calcul(14, 6, 1946, 40)).unwrap();
+    image.pixels().map(|p| p.0).collect()
+}
+
+#[test]
+fn sans_accent() {
+    assert_eq!(pixels(None), [[0, 0, 0, 255], [200, 0, 0, 255], [100, 100, 100, 255], [100, 100, 100, 255]]);
+}
+
+#[test]
+fn attenuation() {
+    // Fond éclairci: 255 - 155 × 0.5 = 177, PPR à demi opaque: 177 × 0.5 = 88, cycle intact
+    assert_eq!(pixels(Some(Effet::Attenuation(0.5))), [[88, 88, 88, 255], [200, 0, 0, 255], [177, 177, 177, 255], [177, 177, 177, 255]]);
+    // Opacité hors de 0..1 ramenée à 1: rien n'est estompé
+    assert_eq!(pixels(Some(Effet::Attenuation(3.0))), pixels(None));
+}
+
+#[test]
+fn teinte_du_cycle() {
+    // Rouge mêlé de bleu à moitié: (200 + (0 - 200) × 0.5, 0, 255 × 0.5), PPR et fond inchangés
+    let teinte = pixels(Some(Effet::Teinte { couleur: [0, 0, 255], force: 0.5 }));
+    assert_eq!(teinte, [[0, 0, 0, 255], [100, 0, 127, 255], [100, 100, 100, 255], [100, 100, 100, 255]]);
+    let pleine = pixels(Some(Effet::Teinte { couleur: [0, 0, 255], force: 1.0 }));
+    assert_eq!((pleine[0], pleine[1]), ([0, 0, 0, 255], [0, 0, 255, 255]));
+}
+
+#[test]
+fn calques_a_la_taille_du_fond() {
+    let (template, ids) = template(None);
+    assert_eq!((template.largeur(), template.hauteur()), (4, 1));
+    assert!(template.calques_manquants().iter().all(|c| *c != Nommage::default().nommer(&ids[0])));
+    let erreur = Template::depuis_images(RgbaImage::new(4, 1), vec![("X".to_string(), RgbaImage::new(3, 3))]).err().unwrap();
+    assert_eq!(erreur.to_string(), "Taille de buffer inattendue (document) pour le calque 'X': 36 != 16");
+}
@@ -0,0 +1,22 @@
+use cycles_numerologie_du_tarot::{analyser, calcul, Groupement};
+
+#[test]
+fn arcanes_hors_plage_ignores() {
+    // Année 0: arcane 0 dans le tableau, sans effet sur l'analyse
+    let tableau = calcul(1, 1, 0, 0);
+    assert!(tableau.positions().iter().any(|(_, p)| p.variantes().iter().any(|(_, a)| a.visible() == Some(0))));
+    let analyse = analyser(&tableau, &Groupement::par_defaut());
+    assert!(analyse.repetitions.iter().all(|r| (1..=22).contains(&r.arcane)));
+    // Groupement construit à la main avec un arcane 0
+    let zero = Groupement::new("Zéro", vec![("zéro".to_string(), vec![0, 1])]);
+    assert!(analyser(&tableau, &[zero]).dominances.len() == 1);
+}
+
+#[test]
+fn analyse_tableau() {
+    let analyse = analyser(&calcul(14, 6, 1946, 40), &Groupement::par_defaut());
+    assert!(analyse.total > 0);
+    assert!(analyse.absents.iter().all(|x| analyse.repetitions.iter().all(|r| r.arcane != *x)));
+    let parite = &analyse.dominances[0];
+    assert_eq!(parite.groupes.iter().map(|c| c.nombre).sum::<usize>(), analyse.total);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "image")]
+
+use std::io::Cursor;
+use cycles_numerologie_du_tarot::animation::{encoder_animation, encoder_webp_anime, FormatAnimation};
+use cycles_numerologie_du_tarot::{message_erreur, Langue};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, Frame, Rgba, RgbaImage};
+
+// Trois images unies, la dernière à moitié transparente
+fn images() -> Vec<RgbaImage> {
+    [[200, 40, 40, 255], [40, 200, 40, 255], [40, 40, 200, 128]]
+        .into_iter()
+        .map(|c| RgbaImage::from_pixel(6, 5, Rgba(c)))
+        .collect()
+}
+
+fn verifier(frames: Vec<Frame>, exactes: bool) {
+    let images = images();
+    assert_eq!(frames.len(), images.len());
+    for (frame, image) in frames.iter().zip(images.iter()) {
+        assert_eq!(frame.delay().numer_denom_ms(), (250, 1));
+        assert_eq!(frame.buffer().dimensions(), image.dimensions());
+        if exactes {
+            assert_eq!(frame.buffer(), image);
+        }
+    }
+}
+
+#[test]
+fn animations_relues() {
+    let images = images();
+    let gif = encoder_animation(&images, 250, FormatAnimation::Gif).unwrap();
+    verifier(GifDecoder::new(Cursor::new(gif)).unwrap().into_frames().collect_frames().unwrap(), false);
+    let apng = encoder_animation(&images, 250, FormatAnimation::Apng).unwrap();
+    verifier(PngDecoder::new(Cursor::new(apng)).unwrap().apng().unwrap().into_frames().collect_frames().unwrap(), true);
+    let webp = encoder_animation(&images, 250, FormatAnimation::Webp).unwrap();
+    verifier(WebPDecoder::new(Cursor::new(webp)).unwrap().into_frames().collect_frames().unwrap(), true);
+}
+
+#[test]
+fn animation_invalide() {
+    let vide = encoder_animation(&[], 250, FormatAnimation::Gif).unwrap_err();
+    assert_eq!(message_erreur(vide.as_ref(), Langue::Anglais), "Animation without any image");
+    // Image 0×0: refusée avant de calculer la taille du canevas
+    for format in [FormatAnimation::Gif, FormatAnimation::Apng, FormatAnimation::Webp] {
+        let vide = encoder_animation(&[RgbaImage::new(0, 0)], 250, format).unwrap_err();
+        assert_eq!(vide.to_string(), "Animation sans image");
+    }
+    assert_eq!(encoder_webp_anime(&[RgbaImage::new(0, 3)], 250).unwrap_err().to_string(), "Animation sans image");
+    let tailles = [RgbaImage::new(4, 4), RgbaImage::new(5, 4)];
+    let tailles = encoder_animation(&tailles, 250, FormatAnimation::Apng).unwrap_err();
+    assert_eq!(tailles.to_string(), "Animation: images de tailles différentes");
+}
@@ -0,0 +1,154 @@
+use cycles_numerologie_du_tarot::{calcul, calques, Tableau};
+
+// Variantes visibles de chaque position, "-" = masquée
+fn variantes(tableau: &Tableau) -> Vec<String> {
+    tableau
+        .positions()
+        .iter()
+        .map(|(_, p)| p.variantes().iter().map(|(_, a)| a.visible().map(|x| x.to_string()).unwrap_or("-".to_string())).collect::<Vec<_>>().join("/"))
+        .collect()
+}
+
+#[test]
+fn tableau_reference() {
+    // Tableau de référence, sans décalage du mois
+    let tableau = calcul(14, 6, 1946, 40);
+    assert_eq!(
+        variantes(&tableau),
+        [
+            "14/-/5/-", "6/-/-/-", "20/-/2/-", "20/-/11/11", "8/-/8/8", "7/-/7/-", "10/-/19/-", "4/-/13/-",
+            "5/-/5/-", "9/-/18/-", "13/-/4/-",
+            "5/-/5/-", "12/-/12/-", "19/-/19/-",
+            "5/-/5/-", "15/-/6/-", "7/-/7/-",
+        ]
+    );
+    assert_eq!(
+        calques(&tableau),
+        [
+            "PPRPA-R", "PPRSA-R", "PPPPA-R", "PPPSA-R", "APPPA-R", "APPSA-R", "RPPPA-R", "RPPSA-R", "NEMPA-R", "NEMSA-R", "PNEPA-R", "PNESA-R",
+            "ANEPA-R", "ANESA-R", "RNEPA-R", "RNESA-R", "PEXPA-R", "PEXSA-R", "PPEPA-R", "PPESA-R", "APEPA-R", "APESA-R", "RPEPA-R", "RPESA-R",
+            "PPRPA04", "PPRSA13", "PPPPA05", "PPPSA05", "APPPA09", "APPSA18", "RPPPA13", "RPPSA04", "NEMPA07", "NEMSA07", "PNEPA05", "PNESA05",
+            "ANEPA12", "ANESA12", "RNEPA19", "RNESA19", "PEXPA10", "PEXSA19", "PPEPA05", "PPESA05", "APEPA15", "APESA06", "RPEPA07", "RPESA07",
+        ]
+    );
+}
+
+#[test]
+fn mois_decale_jour_29() {
+    // Jour > 22: mois décalé, variantes B (INT, COI, COE, PEX, PPR, cycles PPR et PEX)
+    let tableau = calcul(29, 12, 1999, 0);
+    assert_eq!(
+        variantes(&tableau),
+        [
+            "11/-/2/-", "12/1/3/1", "10/-/1/-", "5/12/5/3", "22/11/4/2", "21/-/3/-", "9/5/9/5", "6/22/6/4",
+            "1/-/1/-", "7/5/7/5", "13/9/13/9",
+            "1/-/1/-", "22/-/4/-", "7/-/7/-",
+            "1/-/1/-", "10/6/10/6", "19/11/19/11",
+        ]
+    );
+    assert_eq!(
+        calques(&tableau),
+        [
+            "PPRPB-R", "PPRSB-R", "APPPB-R", "APPSB-R", "RPPPB-R", "RPPSB-R", "PEXPB-R", "PEXSB-R", "APEPB-R", "APESB-R", "RPEPB-R", "RPESB-R",
+            "PPRPB22", "PPRSB04", "APPPB05", "APPSB05", "RPPPB09", "RPPSB09", "PEXPB05", "PEXSB05", "APEPB06", "APESB06", "RPEPB11", "RPESB11",
+            "PPRPA-R", "PPRSA-R", "PPPPA-R", "PPPSA-R", "APPPA-R", "APPSA-R", "RPPPA-R", "RPPSA-R", "NEMPA-R", "NEMSA-R", "PNEPA-R", "PNESA-R",
+            "ANEPA-R", "ANESA-R", "RNEPA-R", "RNESA-R", "PEXPA-R", "PEXSA-R", "PPEPA-R", "PPESA-R", "APEPA-R", "APESA-R", "RPEPA-R", "RPESA-R",
+            "PPRPA06", "PPRSA06", "PPPPA01", "PPPSA01", "APPPA07", "APPSA07", "RPPPA13", "RPPSA13", "NEMPA21", "NEMSA03", "PNEPA01", "PNESA01",
+            "ANEPA22", "ANESA04", "RNEPA07", "RNESA07", "PEXPA09", "PEXSA09", "PPEPA01", "PPESA01", "APEPA10", "APESA10", "RPEPA19", "RPESA19",
+        ]
+    );
+}
+
+#[test]
+fn mois_decale_jour_23() {
+    // Premier jour décalé: PB et SB, aucun SA
+    let tableau = calcul(23, 2, 1987, 40);
+    assert_eq!(
+        variantes(&tableau),
+        [
+            "5/-/-/-", "2/3/-/3", "7/-/-/-", "7/8/-/8", "9/10/-/10", "12/-/-/-", "16/18/-/18", "14/15/-/15",
+            "5/-/-/-", "19/20/-/20", "6/8/-/8",
+            "5/-/-/-", "17/-/-/-", "11/-/-/-",
+            "5/-/-/-", "21/5/-/5", "10/5/-/5",
+        ]
+    );
+    assert_eq!(
+        calques(&tableau),
+        [
+            "PPRPB-R", "PPRSB-R", "APPPB-R", "APPSB-R", "RPPPB-R", "RPPSB-R", "PEXPB-R", "PEXSB-R", "APEPB-R", "APESB-R", "RPEPB-R", "RPESB-R",
+            "PPRPB15", "PPRSB15", "APPPB20", "APPSB20", "RPPPB08", "RPPSB08", "PEXPB18", "PEXSB18", "APEPB05", "APESB05", "RPEPB05", "RPESB05",
+            "PPRPA-R", "PPPPA-R", "APPPA-R", "RPPPA-R", "NEMPA-R", "PNEPA-R", "ANEPA-R", "RNEPA-R", "PEXPA-R", "PPEPA-R", "APEPA-R", "RPEPA-R",
+            "PPRPA14", "PPPPA05", "APPPA19", "RPPPA06", "NEMPA12", "PNEPA05", "ANEPA17", "RNEPA11", "PEXPA16", "PPEPA05", "APEPA21", "RPEPA10",
+        ]
+    );
+}
+
+#[test]
+fn sans_decalage_jour_22() {
+    // Veille du décalage: ni PB ni SB
+    let tableau = calcul(22, 2, 1987, 40);
+    assert_eq!(
+        variantes(&tableau),
+        [
+            "22/-/4/-", "2/-/-/-", "7/-/-/-", "6/-/6/6", "9/-/-/-", "11/-/11/-", "15/-/15/-", "4/-/13/-",
+            "5/-/5/-", "9/-/18/-", "13/-/4/-",
+            "5/-/5/-", "16/-/16/-", "9/-/9/-",
+            "5/-/5/-", "20/-/20/-", "8/-/8/-",
+        ]
+    );
+    assert_eq!(
+        calques(&tableau),
+        [
+            "PPRPA-R", "PPRSA-R", "PPPPA-R", "PPPSA-R", "APPPA-R", "APPSA-R", "RPPPA-R", "RPPSA-R", "NEMPA-R", "NEMSA-R", "PNEPA-R", "PNESA-R",
+            "ANEPA-R", "ANESA-R", "RNEPA-R", "RNESA-R", "PEXPA-R", "PEXSA-R", "PPEPA-R", "PPESA-R", "APEPA-R", "APESA-R", "RPEPA-R", "RPESA-R",
+            "PPRPA04", "PPRSA13", "PPPPA05", "PPPSA05", "APPPA09", "APPSA18", "RPPPA13", "RPPSA04", "NEMPA11", "NEMSA11", "PNEPA05", "PNESA05",
+            "ANEPA16", "ANESA16", "RNEPA09", "RNESA09", "PEXPA15", "PEXSA15", "PPEPA05", "PPESA05", "APEPA20", "APESA20", "RPEPA08", "RPESA08",
+        ]
+    );
+}
+
+#[test]
+fn variantes_s_masquees() {
+    // Aucune variante S visible
+    let tableau = calcul(1, 1, 2000, 10);
+    assert_eq!(
+        variantes(&tableau),
+        [
+            "1/-/-/-", "1/-/-/-", "2/-/-/-", "2/-/-/-", "3/-/-/-", "3/-/-/-", "5/-/-/-", "4/-/-/-",
+            "11/-/-/-", "15/-/-/-", "19/-/-/-",
+            "11/-/-/-", "14/-/-/-", "17/-/-/-",
+            "11/-/-/-", "16/-/-/-", "21/-/-/-",
+        ]
+    );
+    assert_eq!(
+        calques(&tableau),
+        [
+            "PPRPA-R", "PPPPA-R", "APPPA-R", "RPPPA-R", "NEMPA-R", "PNEPA-R", "ANEPA-R", "RNEPA-R", "PEXPA-R", "PPEPA-R", "APEPA-R", "RPEPA-R",
+            "PPRPA04", "PPPPA11", "APPPA15", "RPPPA19", "NEMPA03", "PNEPA11", "ANEPA14", "RNEPA17", "PEXPA05", "PPEPA11", "APEPA16", "RPEPA21",
+        ]
+    );
+}
+
+#[test]
+fn sa_egal_pa() {
+    // SA égal à PA dessiné pour COE, NEM, PEX, PPR et les cycles
+    let tableau = calcul(11, 11, 1911, 25);
+    assert_eq!(
+        variantes(&tableau),
+        [
+            "11/-/2/-", "11/-/2/-", "12/-/3/-", "22/-/4/4", "5/-/5/5", "5/-/5/-", "9/-/9/-", "7/-/7/-",
+            "8/-/8/-", "15/-/15/-", "22/-/22/-",
+            "8/-/8/-", "13/-/13/-", "18/-/18/-",
+            "8/-/8/-", "17/-/17/-", "8/-/8/-",
+        ]
+    );
+    assert_eq!(
+        calques(&tableau),
+        [
+            "PPRPA-R", "PPRSA-R", "PPPPA-R", "PPPSA-R", "APPPA-R", "APPSA-R", "RPPPA-R", "RPPSA-R", "NEMPA-R", "NEMSA-R", "PNEPA-R", "PNESA-R",
+            "ANEPA-R", "ANESA-R", "RNEPA-R", "RNESA-R", "PEXPA-R", "PEXSA-R", "PPEPA-R", "PPESA-R", "APEPA-R", "APESA-R", "RPEPA-R", "RPESA-R",
+            "PPRPA07", "PPRSA07", "PPPPA08", "PPPSA08", "APPPA15", "APPSA15", "RPPPA22", "RPPSA22", "NEMPA05", "NEMSA05", "PNEPA08", "PNESA08",
+            "ANEPA13", "ANESA13", "RNEPA18", "RNESA18", "PEXPA09", "PEXSA09", "PPEPA08", "PPESA08", "APEPA17", "APESA17", "RPEPA08", "RPESA08",
+        ]
+    );
+}
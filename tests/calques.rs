@@ -0,0 +1,180 @@
+use cycles_numerologie_du_tarot::calque::ErreurCalque;
+use cycles_numerologie_du_tarot::nom::ids_calques_nom;
+use cycles_numerologie_du_tarot::{calcul, calques_attendus, ids_calques, ids_calques_attendus, Accent, Couche, Definitions, Effet, Genre, Langue, LayerId, Nommage, numerologie_nom, OptionsRendu, Tableau, TableLettres, VarianteCalque, VisibilityPolicy};
+
+// Chaque valeur a exactement un cadre dans la liste, chaque cadre appartient à une valeur
+fn verifier_cadres(calques: &[LayerId]) {
+    for valeur in calques.iter().filter(|c| c.kind == Genre::Valeur) {
+        let cadre = valeur.cadre().expect("une valeur a un cadre");
+        assert_eq!(calques.iter().filter(|c| **c == cadre).count(), 1, "cadre de {} ({})", valeur, cadre);
+    }
+    for cadre in calques.iter().filter(|c| c.kind == Genre::Cadre) {
+        assert!(calques.iter().any(|v| v.cadre().as_ref() == Some(cadre)), "cadre sans valeur: {}", cadre);
+        assert_eq!(cadre.cadre(), None);
+    }
+}
+
+#[test]
+fn calques_attendus_un_cadre_par_valeur() {
+    let calques = ids_calques_attendus();
+    verifier_cadres(&calques);
+    assert_eq!(calques.iter().filter(|c| c.kind == Genre::Valeur).count() % 22, 0);
+}
+
+#[test]
+fn calques_dessines_un_cadre_par_valeur() {
+    let attendus = ids_calques_attendus();
+    for a in [1900, 1946, 1987, 1999, 2024] {
+        for m in 1..=12 {
+            for j in 1..=31 {
+                for age in [0, 9, 21, 44, 79] {
+                    let calques = ids_calques(&calcul(j, m, a, age));
+                    verifier_cadres(&calques);
+                    for c in calques.iter() {
+                        assert!(attendus.contains(c), "{} absent des calques attendus", c);
+                    }
+                    // Le cadre est dessiné avant sa valeur
+                    for (i, valeur) in calques.iter().enumerate().filter(|(_, c)| c.kind == Genre::Valeur) {
+                        let cadre = valeur.cadre().unwrap();
+                        assert!(calques[..i].contains(&cadre), "{} avant {}", cadre, valeur);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cadre_pulsion_ppr_sa() {
+    // PPP SA dessiné dès que PPR SA est visible, avec son cadre
+    let cadre = LayerId::cadre_de("PPP", VarianteCalque::SA);
+    let dessine = (1..=28).flat_map(|j| (1..=12).map(move |m| ids_calques(&calcul(j, m, 1987, 30)))).find(|calques| calques.iter().any(|c| c.section == "PPP" && c.variant == VarianteCalque::SA));
+    assert!(dessine.expect("PPP SA dessiné au moins une fois").contains(&cadre));
+    assert!(ids_calques_attendus().contains(&cadre));
+}
+
+#[test]
+fn texte_aller_retour() {
+    let ids = ids_calques_attendus();
+    for (id, texte) in ids.iter().zip(calques_attendus()) {
+        assert_eq!(id.to_string(), texte);
+        assert_eq!(texte.parse::<LayerId>().as_ref(), Ok(id));
+    }
+}
+
+#[test]
+fn lecture_calques() {
+    assert_eq!("APESB14".parse(), Ok(LayerId::valeur("APE", VarianteCalque::SB, 14)));
+    assert_eq!("APESB-R".parse(), Ok(LayerId::cadre_de("APE", VarianteCalque::SB)));
+    assert_eq!("NOMPA01".parse(), Ok(LayerId::valeur("NOM", VarianteCalque::PA, 1)));
+    assert_eq!(LayerId::valeur("PPR", VarianteCalque::PA, 3).to_string(), "PPRPA03");
+    for invalide in ["", "PA01", "PA-R", "APEXX14", "APESB23", "APESB00", "APESB1", "APESB-"] {
+        assert!(invalide.parse::<LayerId>().is_err(), "{} accepté", invalide);
+    }
+    assert_eq!("APESB23".parse::<LayerId>(), Err(ErreurCalque::Arcane { calque: "APESB23".to_string() }));
+    assert_eq!(ErreurCalque::Invalide { calque: "PA01".to_string() }.message(Langue::Anglais), "Invalid layer: 'PA01' (expected e.g. APESB14 or APESB-R)");
+}
+
+#[test]
+fn nommage_par_defaut() {
+    let nommage = Nommage::default();
+    let ids = ids_calques_attendus();
+    for id in ids.iter() {
+        assert_eq!(nommage.nommer(id), id.to_string());
+    }
+    assert_eq!(nommage.verifier(&ids), Ok(()));
+    let sans_zero = Nommage { chiffres: 1, ..Nommage::default() };
+    assert_eq!(sans_zero.nommer(&LayerId::valeur("NEM", VarianteCalque::SA, 7)), "NEMSA7");
+    let sans_arcane = Nommage { valeur: "{section}{variante}".to_string(), ..Nommage::default() };
+    let ambigus = [LayerId::valeur("PPR", VarianteCalque::PA, 1), LayerId::valeur("PPR", VarianteCalque::PA, 2)];
+    let ambigu = ErreurCalque::Ambigu { premier: "PPRPA01".to_string(), second: "PPRPA02".to_string(), calque: "PPRPA".to_string() };
+    assert_eq!(sans_arcane.verifier(&ambigus), Err(ambigu));
+}
+
+#[test]
+fn politiques_de_visibilite() {
+    let attendus = ids_calques_attendus();
+    for (j, m, a) in [(14, 6, 1946), (29, 12, 1999), (23, 2, 1987)] {
+        let tableau = calcul(j, m, a, 40);
+        assert_eq!(VisibilityPolicy::Regles.tableau(&tableau), tableau);
+        let toutes = ids_calques(&VisibilityPolicy::Toutes.tableau(&tableau));
+        verifier_cadres(&toutes);
+        assert!(toutes.iter().all(|c| attendus.contains(c)));
+        assert!(ids_calques(&tableau).iter().all(|c| toutes.contains(c)));
+        let principales = ids_calques(&VisibilityPolicy::Principales.tableau(&tableau));
+        assert!(principales.iter().all(|c| matches!(c.variant, VarianteCalque::PA | VarianteCalque::PB)));
+        let sans_b = ids_calques(&VisibilityPolicy::Predicat(|v| v.arcane.visible && !v.variante.ends_with('B')).tableau(&tableau));
+        assert!(sans_b.iter().all(|c| !c.variant.est_b()));
+    }
+}
+
+#[test]
+fn options_de_rendu() {
+    let tableau = calcul(23, 2, 1987, 40);
+    let defaut = ids_calques(&tableau);
+    assert_eq!(OptionsRendu::default().calques_tableau(&tableau), defaut);
+
+    // Une seule section: PPR et son cycle, ordre habituel conservé
+    let ppr = OptionsRendu { sections: vec!["PPR".to_string()], ..OptionsRendu::default() }.calques_tableau(&tableau);
+    let sections_ppr = ["PPR", "PPP", "APP", "RPP"];
+    assert!(!ppr.is_empty());
+    assert_eq!(ppr, defaut.iter().filter(|c| sections_ppr.contains(&c.section.as_str())).cloned().collect::<Vec<_>>());
+
+    // Une image par section: mêmes calques, répartis
+    let par_section = OptionsRendu::default().calques_par_section_tableau(&tableau);
+    assert_eq!(par_section.iter().map(|(s, _)| s.as_str()).collect::<Vec<_>>(), ["PPR", "NEM", "PEX"]);
+    assert_eq!(par_section.iter().map(|(_, c)| c.len()).sum::<usize>(), defaut.len());
+
+    // Valeurs seulement, section par section dans l'ordre demandé
+    let options = OptionsRendu {
+        sections: vec!["PEX".to_string(), "PPR".to_string()],
+        couches: vec![Couche::ValeursA, Couche::ValeursB],
+        par_section: true,
+        ..OptionsRendu::default()
+    };
+    let calques = options.calques_tableau(&tableau);
+    assert!(calques.iter().all(|c| c.kind == Genre::Valeur));
+    let premier_ppr = calques.iter().position(|c| c.section == "PPR").unwrap();
+    assert!(calques[..premier_ppr].iter().all(|c| ["PEX", "PPE", "APE", "RPE"].contains(&c.section.as_str())));
+    assert!(calques[premier_ppr..].iter().all(|c| sections_ppr.contains(&c.section.as_str())));
+    assert!(options.verifier(&Definitions::integree()).is_ok());
+    let inconnue = OptionsRendu { sections: vec!["XXX".to_string()], ..OptionsRendu::default() }.verifier(&Definitions::integree()).unwrap_err();
+    assert!(inconnue.message(Langue::Anglais).starts_with("Unknown section: XXX ("));
+}
+
+#[test]
+fn calques_du_nom() {
+    let nom = numerologie_nom("Marie", "Curie", &TableLettres::pythagoricienne());
+    let defaut = ids_calques_nom(&nom, &OptionsRendu::default());
+    verifier_cadres(&defaut);
+    assert!(defaut.iter().all(|c| matches!(c.variant, VarianteCalque::PA | VarianteCalque::SA)));
+
+    // Toutes: chaque SA dessiné, même égal à PA
+    let toutes = ids_calques_nom(&nom, &OptionsRendu { visibilite: VisibilityPolicy::Toutes, ..OptionsRendu::default() });
+    assert_eq!(toutes.iter().filter(|c| c.kind == Genre::Valeur && c.variant == VarianteCalque::SA).count(), 5);
+    assert!(defaut.iter().all(|c| toutes.contains(c)));
+
+    let principales = ids_calques_nom(&nom, &OptionsRendu { visibilite: VisibilityPolicy::Principales, ..OptionsRendu::default() });
+    assert!(principales.iter().all(|c| c.variant == VarianteCalque::PA));
+    assert_eq!(principales.iter().filter(|c| c.kind == Genre::Valeur).count(), 5);
+
+    // Couches demandées seulement
+    let valeurs = ids_calques_nom(&nom, &OptionsRendu { couches: vec![Couche::ValeursA], ..OptionsRendu::default() });
+    assert_eq!(valeurs, defaut.iter().filter(|c| c.kind == Genre::Valeur).cloned().collect::<Vec<_>>());
+}
+
+#[test]
+fn cycles_voisins() {
+    let tableau = calcul(14, 6, 1946, 79);
+    assert!(OptionsRendu::default().calques_voisins_tableau(&tableau).is_empty());
+    let accent = Accent { effet: Effet::Attenuation(0.35), voisins: Some(0.25) };
+    let options = OptionsRendu { accent: Some(accent), ..OptionsRendu::default() };
+    let voisins = options.calques_voisins_tableau(&tableau);
+    let cycle = ["PPP", "APP", "RPP", "PNE", "ANE", "RNE", "PPE", "APE", "RPE"];
+    assert!(voisins.iter().all(|c| cycle.contains(&c.section.as_str())));
+    // Pulsion de l'âge 78 (79 -> 16) et de l'âge 80 (81 -> 9)
+    assert!(voisins.contains(&LayerId::valeur("PPP", VarianteCalque::PA, 16)));
+    assert!(voisins.contains(&LayerId::valeur("PPP", VarianteCalque::PA, 9)));
+    // Tableau combiné, sans date: pas de voisins
+    assert!(options.calques_voisins_tableau(&Tableau { annee: 0, ..tableau }).is_empty());
+}
@@ -0,0 +1,36 @@
+use cycles_numerologie_du_tarot::compatibilite::combiner;
+use cycles_numerologie_du_tarot::tableau::cycles;
+use cycles_numerologie_du_tarot::{calcul, compatibility, Arcane};
+
+#[test]
+fn couple_de_reference() {
+    // A: 14.06.1946 à 40 ans, B: 23.02.1987 à 10 ans
+    let (a, b) = (calcul(14, 6, 1946, 40), calcul(23, 2, 1987, 10));
+    let c = compatibility(&a, &b);
+    assert_eq!((c.a, c.b), (a, b));
+    assert_eq!((c.couple.jour, c.couple.annee, c.couple.age), (0, 0, 0));
+
+    // Sommes réduites: 4 + 14, 13 + 14 = 27 -> 9, 13 + 15 = 28 -> 10
+    let ppr = c.couple.ppr;
+    assert_eq!([ppr.pa.valeur, ppr.pb.valeur, ppr.sa.valeur, ppr.sb.valeur], [18, 19, 9, 10]);
+    assert_eq!((c.couple.cai.pa, c.couple.nem.pa), (Arcane::new(19, true), Arcane::new(19, true)));
+
+    // Visible si elle l'est chez l'un des deux: PB chez B seul, SA chez A seul
+    assert_eq!((a.ppr.pb.visible, b.ppr.pb.visible, ppr.pb.visible), (false, true, true));
+    assert_eq!((a.ppr.sa.visible, b.ppr.sa.visible, ppr.sa.visible), (true, false, true));
+    assert_eq!((a.cai.sb.visible, b.cai.sb.visible, c.couple.cai.sb.visible), (false, false, false));
+
+    // Cycle commun: pulsion 5 + 11 = 16, action 16 + 18 -> 7, réaction 18 + 7 -> 7
+    let cycle = c.couple.cycle_ppr;
+    assert_eq!([cycle.pulsion.pa.valeur, cycle.action.pa.valeur, cycle.reaction.pa.valeur], [16, 7, 7]);
+    assert_eq!([cycle.action.pb.valeur, cycle.reaction.pb.valeur], [8, 9]);
+    let attendus = cycles(16, &c.couple.ppr, &c.couple.nem, &c.couple.pex);
+    assert_eq!([c.couple.cycle_ppr, c.couple.cycle_nem, c.couple.cycle_pex], attendus);
+}
+
+#[test]
+fn combinaison_commutative() {
+    let (a, b) = (calcul(14, 6, 1946, 40), calcul(23, 2, 1987, 10));
+    assert_eq!(compatibility(&a, &b).couple, compatibility(&b, &a).couple);
+    assert_eq!(combiner(&[a, b]), compatibility(&a, &b).couple);
+}
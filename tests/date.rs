@@ -0,0 +1,44 @@
+use cycles_numerologie_du_tarot::Date;
+
+#[test]
+fn formats_acceptes() {
+    let attendue = Date::new(14, 6, 1946).unwrap();
+    for texte in ["14.06.1946", "14/06/1946", "1946-06-14", " 14.6.1946 "] {
+        assert_eq!(texte.parse::<Date>(), Ok(attendue), "{}", texte);
+    }
+    assert_eq!(attendue.to_string(), "14.06.1946");
+}
+
+#[test]
+fn dates_refusees() {
+    for texte in ["", "14.06", "14.06.1946.1", "x.06.1946", "1946-06", "32.01.1990", "29.02.1900", "31.04.2000", "00.01.2000", "01.13.2000", "01.00.2000"] {
+        assert!(texte.parse::<Date>().is_err(), "{} accepté", texte);
+    }
+    // Année 0: réservée au tableau combiné
+    assert!("01.01.0000".parse::<Date>().is_err());
+    assert!(Date::new(1, 1, 0).is_err());
+}
+
+#[test]
+fn annees_bissextiles() {
+    assert!(Date::new(29, 2, 2000).is_ok());
+    assert!(Date::new(29, 2, 2024).is_ok());
+    assert!(Date::new(29, 2, 1900).is_err());
+    assert!(Date::new(29, 2, 2023).is_err());
+}
+
+#[test]
+fn age_revolu() {
+    let naissance = Date::new(14, 6, 1946).unwrap();
+    assert_eq!(naissance.age_au(&Date::new(13, 6, 1986).unwrap()), Some(39));
+    assert_eq!(naissance.age_au(&Date::new(14, 6, 1986).unwrap()), Some(40));
+    assert_eq!(naissance.age_au(&naissance), Some(0));
+    assert_eq!(naissance.age_au(&Date::new(13, 6, 1946).unwrap()), None);
+}
+
+#[test]
+fn lendemain() {
+    assert_eq!(Date::new(28, 2, 2024).unwrap().lendemain(), Date::new(29, 2, 2024).unwrap());
+    assert_eq!(Date::new(28, 2, 2023).unwrap().lendemain(), Date::new(1, 3, 2023).unwrap());
+    assert_eq!(Date::new(31, 12, 1999).unwrap().lendemain(), Date::new(1, 1, 2000).unwrap());
+}
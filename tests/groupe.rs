@@ -0,0 +1,35 @@
+use cycles_numerologie_du_tarot::groupe::{constellation, Membre};
+use cycles_numerologie_du_tarot::compatibilite::combiner;
+use cycles_numerologie_du_tarot::calcul;
+
+fn membre(nom: &str, j: usize, m: usize, a: usize, age: usize) -> Membre {
+    Membre { nom: nom.to_string(), tableau: calcul(j, m, a, age) }
+}
+
+#[test]
+fn constellation_de_trois() {
+    let membres = [membre("A", 14, 6, 1946, 40), membre("B", 23, 2, 1987, 10), membre("C", 5, 4, 1994, 20)];
+    let c = constellation(&membres);
+    assert_eq!(c.positions.iter().map(|p| p.position.as_str()).collect::<Vec<_>>(), ["CAI", "INT", "CAE", "COI", "COE", "NEM", "PEX", "PPR"]);
+
+    // CAI: jours 14, 23 et 5 -> 14 et 1 + 4 = 5, 2 + 3 = 5, 5: le 5 est partagé par les trois
+    let cai = &c.positions[0];
+    assert_eq!(cai.par_membre, [vec![5, 14], vec![5], vec![5]]);
+    assert_eq!(cai.partages, [5]);
+    assert_eq!(cai.manquants, (1..=22).filter(|x| ![5, 14].contains(x)).collect::<Vec<_>>());
+
+    // PPR: 14 chez B et C seulement, le 4 et le 13 de A ne sont pas partagés
+    let ppr = &c.positions[7];
+    assert_eq!(ppr.par_membre, [vec![4, 13], vec![14, 15], vec![14]]);
+    assert_eq!(ppr.partages, [14]);
+    assert!(ppr.manquants.iter().all(|x| ppr.par_membre.iter().all(|m| !m.contains(x))));
+    assert_eq!(ppr.manquants.len(), 22 - 4);
+
+    // INT: aucun arcane commun
+    assert!(c.positions[1].partages.is_empty());
+
+    // Manquants du groupe: absents de toutes les positions de base
+    assert_eq!(c.manquants, [1, 17, 21, 22]);
+    assert_eq!(c.groupe, combiner(&membres.map(|m| m.tableau)));
+    assert_eq!(ppr.groupe, c.groupe.ppr);
+}
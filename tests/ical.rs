@@ -0,0 +1,57 @@
+use cycles_numerologie_du_tarot::ical::{ical, OptionsIcal};
+use cycles_numerologie_du_tarot::{Date, Langue};
+
+fn options(annees: std::ops::RangeInclusive<usize>, nom: Option<&str>) -> OptionsIcal {
+    OptionsIcal { annees, mois_personnels: false, nom: nom.map(|n| n.to_string()), langue: Langue::Francais }
+}
+
+// Lignes logiques: suites (espace en tête) recollées à la ligne précédente
+fn deplier(ics: &str) -> Vec<String> {
+    ics.replace("\r\n ", "").split("\r\n").filter(|l| !l.is_empty()).map(|l| l.to_string()).collect()
+}
+
+#[test]
+fn pliage_a_75_octets() {
+    let nom = "Zoé Lefèvre ".repeat(12);
+    let ics = ical(&Date::new(14, 6, 1946).unwrap(), &options(1986..=1986, Some(&nom)));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    // Fin de ligne CRLF seulement, aucune ligne physique au-delà de 75 octets
+    assert!(!ics.replace("\r\n", "").contains('\n'));
+    let physiques: Vec<&str> = ics.split("\r\n").collect();
+    assert!(physiques.iter().all(|l| l.len() <= 75));
+    // Les caractères de deux octets ne sont jamais coupés: le titre déplié est intact
+    let titre = deplier(&ics).into_iter().find(|l| l.starts_with("SUMMARY:")).unwrap();
+    assert_eq!(titre, format!("SUMMARY:Nouveau cycle annuel: 5 (40 ans) - {}", nom));
+    let suites = physiques.iter().filter(|l| l.starts_with(' ')).count();
+    assert!(suites >= titre.len() / 75);
+    // Une ligne pliée au plus près: 75 octets, ou 74 si le caractère suivant en fait deux
+    let premiere = physiques.iter().find(|l| l.starts_with("SUMMARY:")).unwrap();
+    assert!((74..=75).contains(&premiere.len()));
+}
+
+#[test]
+fn texte_echappe() {
+    let ics = ical(&Date::new(14, 6, 1946).unwrap(), &options(1986..=1986, Some("A, B; C\\D\nE")));
+    let lignes = deplier(&ics);
+    let titre = lignes.iter().find(|l| l.starts_with("SUMMARY:")).unwrap();
+    assert!(titre.ends_with(r" - A\, B\; C\\D\nE"), "{}", titre);
+    // Description sur plusieurs lignes: \n échappé
+    let description = lignes.iter().find(|l| l.starts_with("DESCRIPTION:")).unwrap();
+    assert!(description.contains("\\n\\n") && !description.contains('\n'));
+}
+
+#[test]
+fn anniversaire_du_29_fevrier() {
+    let naissance = Date::new(29, 2, 2000).unwrap();
+    let ics = ical(&naissance, &options(2001..=2004, None));
+    let debuts: Vec<String> = deplier(&ics).into_iter().filter(|l| l.starts_with("DTSTART")).collect();
+    assert_eq!(debuts, [
+        "DTSTART;VALUE=DATE:20010301",
+        "DTSTART;VALUE=DATE:20020301",
+        "DTSTART;VALUE=DATE:20030301",
+        "DTSTART;VALUE=DATE:20040229",
+    ]);
+    assert!(deplier(&ics).contains(&"DTEND;VALUE=DATE:20040301".to_string()));
+    // Années avant la naissance: aucun événement
+    assert!(!ical(&naissance, &options(1990..=1999, None)).contains("BEGIN:VEVENT"));
+}
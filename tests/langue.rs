@@ -0,0 +1,35 @@
+use std::collections::BTreeSet;
+use cycles_numerologie_du_tarot::{message_erreur, Date, Erreur, ErreurDate, Groupement, Langue};
+
+fn identifiants(catalogue: &str) -> BTreeSet<&str> {
+    catalogue
+        .lines()
+        .filter(|l| !l.starts_with('#') && !l.starts_with(' '))
+        .filter_map(|l| l.split_once(" = ").map(|(id, _)| id))
+        .collect()
+}
+
+#[test]
+fn catalogues_complets() {
+    let fr = identifiants(include_str!("../src/locales/fr.ftl"));
+    let en = identifiants(include_str!("../src/locales/en.ftl"));
+    assert_eq!(fr.difference(&en).collect::<Vec<_>>(), Vec::<&&str>::new());
+    assert_eq!(en.difference(&fr).collect::<Vec<_>>(), Vec::<&&str>::new());
+}
+
+#[test]
+fn erreurs_traduites() {
+    let jour = "31.02.1990".parse::<Date>().unwrap_err();
+    assert_eq!(jour, ErreurDate::Jour { jour: 31, mois: 2, annee: 1990 });
+    assert_eq!(jour.to_string(), "Jour invalide: 31.02.1990");
+    assert_eq!(message_erreur(&jour, Langue::Anglais), "Invalid day: 31.02.1990");
+
+    // Cause traduite elle aussi
+    let ligne = Erreur::new("erreur-csv-ligne", &[("ligne", "3".to_string())]).avec_cause(jour);
+    assert_eq!(ligne.to_string(), "CSV ligne 3: Jour invalide: 31.02.1990");
+    assert_eq!(message_erreur(&ligne, Langue::Anglais), "CSV line 3: Invalid day: 31.02.1990");
+
+    let groupement = "Éléments".parse::<Groupement>().unwrap_err();
+    assert_eq!(groupement.message(Langue::Anglais), "Invalid grouping: 'Éléments' (expected name:group=1,2;other=3,4)");
+    assert_eq!("xx".parse::<Langue>().unwrap_err().message(Langue::Anglais), "Unsupported language: 'xx' (fr, en)");
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "lot")]
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use cycles_numerologie_du_tarot::lot::{lire_clients, nom_fichier, rendre_lot_avec, OptionsLot};
+use cycles_numerologie_du_tarot::{Date, Langue};
+
+const CSV: &str = "nom,jour,mois,annee,age,lecture
+Jean Dupont,14,6,1946,40,
+Marie,29,12,1999,,01.01.2030
+Futur,1,1,2040,,
+Échec,23,2,1987,10,
+";
+
+// Dossier vide propre au test
+fn dossier(nom: &str) -> PathBuf {
+    let dossier = std::env::temp_dir().join(format!("cycles_lot_{}_{}", nom, std::process::id()));
+    let _ = fs::remove_dir_all(&dossier);
+    fs::create_dir_all(&dossier).unwrap();
+    dossier
+}
+
+fn options(dossier: &Path, reprendre: bool) -> OptionsLot {
+    OptionsLot { lecture: Date::new(1, 1, 2026).unwrap(), reprendre, langue: Langue::Anglais, ..OptionsLot::new(dossier) }
+}
+
+// PNG factice: la naissance 1987 échoue, chaque appel est compté
+fn rendre(appels: &AtomicUsize) -> impl Fn(Date, usize) -> Result<Vec<u8>, Box<dyn Error>> + Sync + '_ {
+    move |n, age| {
+        appels.fetch_add(1, Ordering::SeqCst);
+        if n.annee == 1987 {
+            return Err("rendu impossible".into());
+        }
+        Ok(format!("{} {}", n, age).into_bytes())
+    }
+}
+
+#[test]
+fn clients_lus() {
+    let dossier = dossier("clients");
+    let csv = dossier.join("clients.csv");
+    fs::write(&csv, CSV).unwrap();
+    let clients = lire_clients(csv.to_str().unwrap()).unwrap();
+    assert_eq!(clients.len(), 4);
+    assert_eq!((clients[0].ligne, clients[0].age), (1, Some(40)));
+    assert_eq!(clients[1].lecture, Some(Date::new(1, 1, 2030).unwrap()));
+    assert_eq!(clients[1].age(&Date::new(1, 1, 2026).unwrap()), Ok(30));
+    assert!(clients[2].age(&Date::new(1, 1, 2026).unwrap()).is_err());
+    assert_eq!(nom_fichier("{ligne}_{nom}_{jour}-{mois}-{annee}_{age}.png", &clients[0], 40), "1_Jean_Dupont_14-06-1946_40.png");
+
+    fs::write(&csv, "nom,jour,mois,annee\nJean,31,2,1990\n").unwrap();
+    let erreur = lire_clients(csv.to_str().unwrap()).unwrap_err();
+    assert_eq!(erreur.to_string(), "CSV ligne 1: Jour invalide: 31.02.1990");
+    fs::remove_dir_all(&dossier).unwrap();
+}
+
+#[test]
+fn journal_et_reprise() {
+    let dossier = dossier("reprise");
+    let csv = dossier.join("clients.csv");
+    fs::write(&csv, CSV).unwrap();
+    let clients = lire_clients(csv.to_str().unwrap()).unwrap();
+    let appels = AtomicUsize::new(0);
+
+    // Premier passage: deux PNG, un âge refusé, un rendu en échec
+    let resultats = rendre_lot_avec(&clients, &options(&dossier, false), rendre(&appels)).unwrap();
+    assert_eq!(appels.load(Ordering::SeqCst), 3);
+    assert_eq!(resultats.iter().map(|r| r.erreur.is_none()).collect::<Vec<_>>(), [true, true, false, false]);
+    assert_eq!(resultats[2].erreur.as_deref(), Some("The reading date 01.01.2026 is before the birth date 01.01.2040"));
+    assert_eq!(resultats[3].erreur.as_deref(), Some("rendu impossible"));
+    assert_eq!(fs::read_to_string(dossier.join(&resultats[0].fichier)).unwrap(), "14.06.1946 40");
+    let journal = fs::read_to_string(dossier.join(".cycles_lot_journal")).unwrap();
+    assert_eq!(journal.lines().count(), 2);
+    assert!(!dossier.join(resultats[3].fichier.replace(".png", ".part")).exists());
+
+    // Reprise: les PNG terminés ne sont pas refaits, sauf s'ils ont disparu
+    fs::remove_file(dossier.join(&resultats[1].fichier)).unwrap();
+    appels.store(0, Ordering::SeqCst);
+    let reprise = rendre_lot_avec(&clients, &options(&dossier, true), rendre(&appels)).unwrap();
+    assert_eq!(appels.load(Ordering::SeqCst), 2);
+    assert_eq!(reprise.iter().map(|r| r.repris).collect::<Vec<_>>(), [true, false, false, false]);
+    assert!(dossier.join(&reprise[1].fichier).exists());
+    let journal = fs::read_to_string(dossier.join(".cycles_lot_journal")).unwrap();
+    assert_eq!(journal.lines().count(), 3);
+
+    // Résumé: une ligne par client, statut traduit et colonnes du tableau
+    let resume = fs::read_to_string(dossier.join("resume.csv")).unwrap();
+    let lignes: Vec<&str> = resume.lines().collect();
+    assert_eq!(lignes.len(), 5);
+    assert!(lignes[0].starts_with("ligne,nom,naissance,age,fichier,statut,CAI_PA,CAI_PB"));
+    assert!(lignes[1].starts_with("1,Jean Dupont,14.06.1946,40,1_Jean_Dupont_14-06-1946.png,resumed,14,,5,"));
+    assert!(lignes[2].contains(",ok,"));
+    assert!(lignes[3].starts_with("3,Futur,01.01.2040,,,error: The reading date"));
+    assert!(lignes[4].contains(",error: rendu impossible,"));
+    assert!(dossier.join("resume.json").exists());
+
+    // Sans reprise: journal recommencé, tout est refait
+    appels.store(0, Ordering::SeqCst);
+    rendre_lot_avec(&clients, &options(&dossier, false), rendre(&appels)).unwrap();
+    assert_eq!(appels.load(Ordering::SeqCst), 3);
+    assert_eq!(fs::read_to_string(dossier.join(".cycles_lot_journal")).unwrap().lines().count(), 2);
+    fs::remove_dir_all(&dossier).unwrap();
+}
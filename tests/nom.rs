@@ -0,0 +1,47 @@
+use cycles_numerologie_du_tarot::nom::{sans_accents, sommes, Sommes};
+use cycles_numerologie_du_tarot::{numerologie_nom, Arcane, Position, TableLettres};
+
+#[test]
+fn accents_et_ligatures() {
+    assert_eq!(sans_accents("Zoé Lefèvre-Œ"), "ZOE LEFEVRE-OE");
+    assert_eq!(sans_accents("Ærø straße"), "AERO STRASSE");
+    assert_eq!(sans_accents("ça, Ñuñez, Ÿvonne, Hélène Noël"), "CA, NUNEZ, YVONNE, HELENE NOEL");
+    // Déjà sans accent: en majuscules, ponctuation gardée
+    assert_eq!(sans_accents("jean-paul d'arc"), "JEAN-PAUL D'ARC");
+}
+
+#[test]
+fn tables_de_lettres() {
+    let pythagore = TableLettres::pythagoricienne();
+    let valeurs = |table: &TableLettres, lettres: &str| lettres.chars().map(|c| table.valeur(c).unwrap()).collect::<Vec<_>>();
+    assert_eq!(valeurs(&pythagore, "AIJRSZ"), [1, 9, 1, 9, 1, 8]);
+    assert_eq!(valeurs(&pythagore, "jean"), [1, 5, 1, 5]);
+    assert_eq!((pythagore.valeur('é'), pythagore.valeur('-')), (None, None));
+
+    // Chaldéenne: jamais 9, F et P = 8
+    let chaldee = TableLettres::chaldeenne();
+    assert_eq!(valeurs(&chaldee, "DUPONT"), [4, 6, 8, 7, 5, 4]);
+    assert!(chaldee.valeurs.iter().all(|v| (1..=8).contains(v)));
+    assert_eq!(valeurs(&TableLettres::alphabetique(), "AZ"), [1, 26]);
+    assert_eq!(TableLettres::default(), pythagore);
+}
+
+#[test]
+fn voyelles_et_consonnes() {
+    let table = TableLettres::pythagoricienne();
+    // J1 E5 A1 N5 / D4 U3 P7 O6 N5 T2
+    assert_eq!(sommes("Jean", &table), Sommes { total: 12, voyelles: 6, consonnes: 6 });
+    assert_eq!(sommes("Dupont", &table), Sommes { total: 27, voyelles: 9, consonnes: 18 });
+    // Y voyelle, accent retiré avant le calcul
+    assert_eq!(sommes("Yvé", &table), Sommes { total: 16, voyelles: 12, consonnes: 4 });
+    assert_eq!(sommes("Chaldée", &TableLettres::chaldeenne()).total, 3 + 5 + 1 + 3 + 4 + 5 + 5);
+
+    // Prénom 12, nom 27 -> 9, complet 39 -> 12, voyelles 15, consonnes 24 -> 6
+    let n = numerologie_nom("Jean", "Dupont", &table);
+    let pa_sa = |p: Position| (p.pa.valeur, p.sa);
+    assert_eq!(pa_sa(n.prenom), (12, Arcane::new(3, true)));
+    assert_eq!(pa_sa(n.nom), (9, Arcane::new(9, false)));
+    assert_eq!(pa_sa(n.complet), (12, Arcane::new(3, true)));
+    assert_eq!(pa_sa(n.voyelles), (15, Arcane::new(6, true)));
+    assert_eq!(pa_sa(n.consonnes), (6, Arcane::new(6, false)));
+}
@@ -0,0 +1,31 @@
+use cycles_numerologie_du_tarot::{cycles_entre, cycles_personnels, Date};
+
+#[test]
+fn regles_mois_et_jour() {
+    let naissance = Date::new(14, 6, 1946).unwrap();
+    // 40 ans: pulsion de l'année = 40 + 1 -> 4 + 1 = 5, PPR.PA = 4
+    let c = cycles_personnels(&naissance, &Date::new(20, 9, 1986).unwrap()).unwrap();
+    assert_eq!((c.age, c.annee.ppr.pulsion.pa.valeur), (40, 5));
+    // Mois: 5 + 9 = 14, action 14 + 4 = 18, réaction 4 + 18 = 22
+    let mois = c.mois.ppr;
+    assert_eq!([mois.pulsion.pa.valeur, mois.action.pa.valeur, mois.reaction.pa.valeur], [14, 18, 22]);
+    // Jour: 14 + 20 = 34 -> 3 + 4 = 7, action 7 + 4 = 11, réaction 4 + 11 = 15
+    let jour = c.jour.ppr;
+    assert_eq!([jour.pulsion.pa.valeur, jour.action.pa.valeur, jour.reaction.pa.valeur], [7, 11, 15]);
+
+    // Décembre: 5 + 12 = 17, puis 17 + 30 = 47 -> 11
+    let c = cycles_personnels(&naissance, &Date::new(30, 12, 1986).unwrap()).unwrap();
+    assert_eq!((c.mois.ppr.pulsion.pa.valeur, c.jour.ppr.pulsion.pa.valeur), (17, 11));
+    assert_eq!(c.mois.nem.pulsion, c.mois.ppr.pulsion);
+}
+
+#[test]
+fn jours_successifs() {
+    let naissance = Date::new(14, 6, 1946).unwrap();
+    assert_eq!(cycles_personnels(&naissance, &Date::new(13, 6, 1946).unwrap()), None);
+    // Les jours avant la naissance sont sautés
+    let jours: Vec<_> = cycles_entre(&naissance, &Date::new(10, 6, 1946).unwrap(), &Date::new(16, 6, 1946).unwrap()).collect();
+    assert_eq!(jours.iter().map(|c| c.date.jour).collect::<Vec<_>>(), [14, 15, 16]);
+    // Âge 0: pulsion 1, mois 1 + 6 = 7, jours 7 + 14 = 21, 22, puis 23 -> 5
+    assert_eq!(jours.iter().map(|c| c.jour.ppr.pulsion.pa.valeur).collect::<Vec<_>>(), [21, 22, 5]);
+}
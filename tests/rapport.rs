@@ -0,0 +1,88 @@
+#![cfg(feature = "rapport")]
+
+use cycles_numerologie_du_tarot::rapport::{rendre_rapport, ContexteRapport, FormatRapport};
+use cycles_numerologie_du_tarot::{calcul, Langue};
+
+fn contexte() -> ContexteRapport {
+    ContexteRapport::new(&calcul(14, 6, 1946, 40), Langue::Francais)
+}
+
+#[test]
+fn rapport_markdown() {
+    let md = rendre_rapport(&contexte(), FormatRapport::Markdown, None).unwrap();
+    let debut = "\
+# Cycles de numérologie du tarot
+
+Naissance: 14.06.1946 · Âge: 40
+
+## Positions
+
+| Position | Arcanes |
+|---|---|
+| CAI | PA 14 Tempérance, SA 5 Le Pape |
+| INT | PA 6 L'Amoureux |
+| CAE | PA 20 Le Jugement, SA 2 La Papesse |
+| COI | PA 20 Le Jugement, SA 11 La Force |
+| COE | PA 8 La Justice |
+| NEM | PA 7 Le Chariot |
+| PEX (Personnalité extérieure) | PA 10 La Roue de Fortune, SA 19 Le Soleil |
+| PPR (Personnalité profonde) | PA 4 L'Empereur, SA 13 L'Arcane sans nom |
+
+## Calcul
+
+- **CAI** (jour): 14
+- **INT** (mois): 6
+- **CAE** (année): 1946 → 20
+- **COI** (CAI + INT): 14 + 6 = 20
+- **COE** (CAE + INT): 20 + 6 = 26 → 8
+- **NEM** (CAI + CAE): 14 + 20 = 34 → 7
+- **PEX** (COI + COE): 20 + 8 = 28 → 10
+- **PPR** (CAI + INT + CAE): 14 + 6 + 20 = 40 → 4
+- **PULSION_PPR** (âge + 1): 40 + 1 = 41 → 5
+- **ACTION_PPR** (PULSION + PPR): 5 + 4 = 9
+- **REACTION_PPR** (ACTION + PPR): 9 + 4 = 13
+
+## Cycle en cours
+
+- **Pulsion PPR**: 5 Le Pape
+- **Action PPR**: 9 L'Hermite, 18 La Lune
+- **Réaction PPR**: 13 L'Arcane sans nom, 4 L'Empereur
+";
+    assert!(md.starts_with(debut), "{}", md);
+    assert!(md.contains("\nArcanes absents: 1, 3, 16, 17, 21, 22\n"));
+    assert!(md.contains("\n- Parité: Impair 13, Pair 12 → **Impair**\n"));
+    // Sans image ni lecture: sections absentes
+    assert!(!md.contains("![") && !md.contains("## Lecture"));
+}
+
+#[test]
+fn rapport_html() {
+    let html = rendre_rapport(&contexte(), FormatRapport::Html, None).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"fr\">\n"));
+    assert!(html.contains("<title>Cycles de numérologie du tarot - 14.06.1946</title>"));
+    // Texte échappé: apostrophes et flèches
+    let positions = "\
+<tr><th>Position</th><th>Arcanes</th></tr>
+<tr><td>CAI</td><td>PA 14 Tempérance, SA 5 Le Pape</td></tr>
+<tr><td>INT</td><td>PA 6 L&#x27;Amoureux</td></tr>
+";
+    assert!(html.contains(positions), "{}", html);
+    assert!(html.contains("<tr><td>PPR</td><td>CAI + INT + CAE</td><td>14 + 6 + 20 = 40 &rarr; 4</td></tr>"));
+    assert!(html.trim_end().ends_with("</body>\n</html>"));
+
+    let anglais = rendre_rapport(&ContexteRapport::new(&calcul(14, 6, 1946, 40), Langue::Anglais), FormatRapport::Html, None).unwrap();
+    assert!(anglais.contains("<html lang=\"en\">"));
+}
+
+#[test]
+fn modele_invalide() {
+    let erreur = rendre_rapport(&contexte(), FormatRapport::Markdown, Some("{% for p in positions %}")).unwrap_err();
+    assert_eq!(erreur.id, "erreur-modele-rapport");
+    assert!(erreur.to_string().starts_with("Modèle de rapport invalide: "));
+    // Modèle lisible, rendu en échec
+    let erreur = rendre_rapport(&contexte(), FormatRapport::Html, Some("{{ inconnue() }}")).unwrap_err();
+    assert_eq!(erreur.id, "erreur-rendu-rapport");
+    // Modèle personnalisé: variables du contexte
+    let texte = rendre_rapport(&contexte(), FormatRapport::Markdown, Some("{{ naissance }} {{ age }}")).unwrap();
+    assert_eq!(texte, "14.06.1946 40");
+}
@@ -0,0 +1,85 @@
+use cycles_numerologie_du_tarot::recherche::{rechercher, Condition, Contrainte};
+use cycles_numerologie_du_tarot::{calcul, Date, Langue};
+
+#[test]
+fn contraintes_lues() {
+    for (texte, position, variante, condition, affichage) in [
+        ("PPR.PA = 19", 7, 0, Condition::Egal(19), "PPR.PA = 19"),
+        ("ppr.pa==19", 7, 0, Condition::Egal(19), "PPR.PA = 19"),
+        (" NEM.PA != 7 ", 5, 0, Condition::Different(7), "NEM.PA != 7"),
+        ("NEM.SA in {7, 16}", 5, 2, Condition::Parmi(vec![7, 16]), "NEM.SA in {7, 16}"),
+        ("INT.PB IN{1,2}", 1, 1, Condition::Parmi(vec![1, 2]), "INT.PB in {1, 2}"),
+        ("PEX.SB present", 6, 3, Condition::Present, "PEX.SB present"),
+        ("PEX.SB présent", 6, 3, Condition::Present, "PEX.SB present"),
+        ("REACTION_PEX.PA absent", 16, 0, Condition::Absent, "REACTION_PEX.PA absent"),
+    ] {
+        let contrainte = texte.parse::<Contrainte>().unwrap();
+        assert_eq!(contrainte, Contrainte { position, variante, condition }, "{}", texte);
+        assert_eq!(contrainte.to_string(), affichage);
+        assert_eq!(affichage.parse::<Contrainte>().unwrap(), contrainte);
+    }
+}
+
+#[test]
+fn contraintes_refusees() {
+    for (texte, id) in [
+        ("PPR = 19", "erreur-contrainte"),
+        ("XXX.PA = 19", "erreur-position"),
+        ("PPR.PC = 19", "erreur-variante"),
+        ("PPR.PA = 0", "erreur-arcane"),
+        ("PPR.PA = 23", "erreur-arcane"),
+        ("PPR.PA != x", "erreur-arcane"),
+        ("PPR.PA in 7, 16", "erreur-liste"),
+        ("PPR.PA in {7, 30}", "erreur-arcane"),
+        ("PPR.PA < 7", "erreur-condition"),
+        ("PPR.PA", "erreur-condition"),
+    ] {
+        assert_eq!(texte.parse::<Contrainte>().unwrap_err().id, id, "{}", texte);
+    }
+    let erreur = "PPR.PC = 19".parse::<Contrainte>().unwrap_err();
+    assert_eq!(erreur.to_string(), "Variante inconnue: 'PC' (PA, PB, SA, SB)");
+    assert_eq!(erreur.message(Langue::Anglais), "Unknown variant: 'PC' (PA, PB, SA, SB)");
+}
+
+#[test]
+fn dates_trouvees() {
+    let (du, au) = (Date::new(1, 6, 1946).unwrap(), Date::new(30, 6, 1946).unwrap());
+    let egal: Vec<Contrainte> = vec!["PPR.PA = 4".parse().unwrap()];
+    let dates = rechercher(&egal, &du, &au, 40);
+    assert!(dates.contains(&Date::new(14, 6, 1946).unwrap()));
+    assert!(dates.iter().all(|d| calcul(d.jour, d.mois, d.annee, 40).ppr.pa.visible() == Some(4)));
+
+    // Présent et absent se partagent la plage
+    let present = rechercher(&["PPR.PB present".parse().unwrap()], &du, &au, 40);
+    let absent = rechercher(&["PPR.PB absent".parse().unwrap()], &du, &au, 40);
+    assert_eq!(present.len() + absent.len(), 30);
+    assert!(!present.is_empty() && present.iter().all(|d| d.jour > 22));
+
+    // Toutes les contraintes à la fois
+    let deux = rechercher(&["PPR.PA = 4".parse().unwrap(), "PPR.PB present".parse().unwrap()], &du, &au, 40);
+    assert!(!deux.is_empty() && deux.iter().all(|d| dates.contains(d) && present.contains(d)));
+    assert_eq!(rechercher(&[], &du, &du, 0), [du]);
+}
+
+#[test]
+fn meme_resultat_que_le_tableau() {
+    // Positions calculées à la demande: mêmes dates que le tableau complet
+    let (du, au) = (Date::new(1, 1, 1990).unwrap(), Date::new(31, 12, 1991).unwrap());
+    let contraintes: Vec<Contrainte> = vec!["REACTION_PEX.SB present".parse().unwrap(), "CAI.PA != 3".parse().unwrap(), "NEM.PA in {7, 16}".parse().unwrap()];
+    let mut attendu = vec![];
+    let mut date = du;
+    while date <= au {
+        if contraintes.iter().all(|c| c.verifie(&calcul(date.jour, date.mois, date.annee, 33))) {
+            attendu.push(date);
+        }
+        date = date.lendemain();
+    }
+    assert!(!attendu.is_empty());
+    assert_eq!(rechercher(&contraintes, &du, &au, 33), attendu);
+
+    // 1900–2100 jour par jour, même en debug
+    let debut = std::time::Instant::now();
+    let dates = rechercher(&["PPR.PA = 19".parse().unwrap(), "NEM.PA in {7, 16}".parse().unwrap()], &Date::new(1, 1, 1900).unwrap(), &Date::new(31, 12, 2100).unwrap(), 40);
+    assert_eq!(dates.len(), 259);
+    assert!(debut.elapsed().as_secs() < 5, "{:?}", debut.elapsed());
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "server")]
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use cycles_numerologie_du_tarot::serveur::{application, format_accept};
+use tower::ServiceExt;
+
+// Statut et corps JSON d'une requête envoyée au routeur, sans template
+async fn envoyer(requete: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let app: Router = application(None);
+    let reponse = app.oneshot(requete).await.unwrap();
+    let statut = reponse.status();
+    let corps = to_bytes(reponse.into_body(), usize::MAX).await.unwrap();
+    (statut, serde_json::from_slice(&corps).unwrap())
+}
+
+fn post(uri: &str, corps: &str, accept: Option<&str>) -> Request<Body> {
+    let mut requete = Request::post(uri).header(header::CONTENT_TYPE, "application/json");
+    if let Some(accept) = accept {
+        requete = requete.header(header::ACCEPT, accept);
+    }
+    requete.body(Body::from(corps.to_string())).unwrap()
+}
+
+#[tokio::test]
+async fn date_invalide() {
+    let (statut, json) = envoyer(post("/chart", r#"{"naissance": "31.02.1990", "age": 30}"#, None)).await;
+    assert_eq!(statut, StatusCode::BAD_REQUEST);
+    assert_eq!(json["erreur"], "Jour invalide: 31.02.1990");
+    let (statut, json) = envoyer(post("/chart", r#"{"naissance": "31.02.1990", "age": 30, "langue": "en"}"#, None)).await;
+    assert_eq!(statut, StatusCode::BAD_REQUEST);
+    assert_eq!(json["erreur"], "Invalid day: 31.02.1990");
+    // Lecture avant la naissance: 422
+    let (statut, _) = envoyer(post("/chart", r#"{"naissance": "14.06.1946", "lecture": "01.01.1940"}"#, None)).await;
+    assert_eq!(statut, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (statut, json) = envoyer(post("/chart", r#"{"naissance": "14.06.1946", "age": 40}"#, None)).await;
+    assert_eq!(statut, StatusCode::OK);
+    assert_eq!(json["ppr"]["pa"]["valeur"], 4);
+}
+
+#[tokio::test]
+async fn negociation_accept() {
+    for (accept, format) in [
+        ("image/png", Some("image/png")),
+        ("image/*", Some("image/png")),
+        ("*/*", Some("image/png")),
+        ("image/svg+xml", Some("image/svg+xml")),
+        ("application/pdf, image/png;q=0.5", Some("application/pdf")),
+        ("text/html", None),
+    ] {
+        assert_eq!(format_accept(accept), format, "{}", accept);
+    }
+    let corps = r#"{"naissance": "14.06.1946", "age": 40, "langue": "en"}"#;
+    // Format refusé avant de chercher le template
+    let (statut, json) = envoyer(post("/render", corps, Some("text/html"))).await;
+    assert_eq!(statut, StatusCode::NOT_ACCEPTABLE);
+    assert!(json["erreur"].as_str().unwrap().contains("text/html"));
+    // Format accepté, sans template: 503
+    for accept in [None, Some("image/svg+xml"), Some("application/pdf")] {
+        let (statut, _) = envoyer(post("/render", corps, accept)).await;
+        assert_eq!(statut, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
+
+#[tokio::test]
+async fn description_openapi() {
+    let (statut, json) = envoyer(Request::get("/openapi.json").body(Body::empty()).unwrap()).await;
+    assert_eq!(statut, StatusCode::OK);
+    assert!(json["openapi"].as_str().unwrap().starts_with("3."));
+    for chemin in ["/health", "/chart", "/render", "/timeline"] {
+        assert!(json["paths"].get(chemin).is_some(), "{}", chemin);
+    }
+    let (statut, json) = envoyer(Request::get("/health").body(Body::empty()).unwrap()).await;
+    assert_eq!((statut, json["template"].as_bool()), (StatusCode::OK, Some(false)));
+}
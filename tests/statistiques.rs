@@ -0,0 +1,30 @@
+use cycles_numerologie_du_tarot::statistiques::statistiques;
+use cycles_numerologie_du_tarot::tableau::NOMS_POSITIONS;
+use cycles_numerologie_du_tarot::{calcul, Date};
+
+#[test]
+fn toutes_les_positions() {
+    let (du, au) = (Date::new(1, 1, 1990).unwrap(), Date::new(31, 12, 1990).unwrap());
+    let enfance = statistiques(&du, &au, 0, None);
+    let stats = statistiques(&du, &au, 40, None);
+    assert_eq!(stats.poids_total, 365.0);
+    assert_eq!(stats.positions.iter().map(|p| p.position.as_str()).collect::<Vec<_>>(), NOMS_POSITIONS);
+    assert_eq!(stats.csv().lines().count(), 1 + 17 * 4 * 22);
+
+    // Positions de base indépendantes de l'âge, cycle différent
+    assert_eq!(stats.positions[..8], enfance.positions[..8]);
+    assert_ne!(stats.positions[8..], enfance.positions[8..]);
+    for p in stats.positions.iter() {
+        assert!(p.variantes[0].presence > 0.0, "{}", p.position);
+        for v in p.variantes.iter() {
+            let total: f64 = v.frequences.iter().sum();
+            assert!((total - v.presence).abs() < 1e-9);
+        }
+    }
+
+    // Rareté au même âge que les statistiques
+    let rarete = stats.rarete(&calcul(14, 6, 1990, stats.age));
+    assert!((0.0..100.0).contains(&rarete));
+    let unique = statistiques(&du, &du, 40, None);
+    assert!(unique.rarete(&calcul(1, 1, 1990, 40)).is_sign_positive());
+}